@@ -0,0 +1,22 @@
+-- Revert creating New World tables
+-- Any New World stores must be removed before restoring the Countdown-only check
+DELETE FROM prices
+	WHERE store_id IN (SELECT id FROM stores WHERE supermarket = 'New World');
+DELETE FROM stores
+	WHERE supermarket = 'New World';
+DELETE FROM products
+	WHERE new_world_id IS NOT NULL;
+
+ALTER TABLE stores
+	DROP CONSTRAINT chk_supermarket_type,
+	ADD CONSTRAINT chk_supermarket_type
+		CHECK (
+			(supermarket = 'Countdown' AND countdown_store_id IS NOT NULL)
+		),
+	DROP COLUMN new_world_store_id;
+
+ALTER TABLE products
+	DROP COLUMN new_world_id;
+
+DROP TABLE new_world_products;
+DROP TABLE new_world_stores;
@@ -0,0 +1,31 @@
+-- Adds the tables required to track New World products and stores.
+BEGIN;
+	CREATE TABLE new_world_stores (
+		id VARCHAR(36) PRIMARY KEY,
+		name VARCHAR(255) NOT NULL
+	);
+
+	CREATE TABLE new_world_products (
+		id SERIAL PRIMARY KEY,
+		name VARCHAR(255) NOT NULL,
+		product_id VARCHAR(32) NOT NULL UNIQUE
+	);
+
+	ALTER TABLE products
+		ADD COLUMN new_world_id INT,
+		ADD CONSTRAINT fk_new_world_product
+			FOREIGN KEY(new_world_id)
+				REFERENCES new_world_products(id);
+
+	ALTER TABLE stores
+		ADD COLUMN new_world_store_id VARCHAR(36) UNIQUE,
+		ADD CONSTRAINT fk_new_world_store_id
+			FOREIGN KEY(new_world_store_id)
+				REFERENCES new_world_stores(id),
+		DROP CONSTRAINT chk_supermarket_type,
+		ADD CONSTRAINT chk_supermarket_type
+			CHECK (
+				(supermarket = 'Countdown' AND countdown_store_id IS NOT NULL)
+				OR (supermarket = 'New World' AND new_world_store_id IS NOT NULL)
+			);
+COMMIT;
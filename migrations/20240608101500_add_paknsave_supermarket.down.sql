@@ -0,0 +1,17 @@
+-- Revert adding Pak'nSave as a supermarket
+-- Postgres does not support removing an enum value, so the type is recreated.
+ALTER TABLE stores
+	DROP CONSTRAINT chk_supermarket_type;
+
+ALTER TYPE supermarket RENAME TO supermarket_old;
+CREATE TYPE supermarket AS ENUM ('Countdown', 'New World');
+ALTER TABLE stores
+	ALTER COLUMN supermarket TYPE supermarket USING supermarket::text::supermarket;
+DROP TYPE supermarket_old;
+
+ALTER TABLE stores
+	ADD CONSTRAINT chk_supermarket_type
+		CHECK (
+			(supermarket = 'Countdown' AND countdown_store_id IS NOT NULL)
+			OR (supermarket = 'New World' AND new_world_store_id IS NOT NULL)
+		);
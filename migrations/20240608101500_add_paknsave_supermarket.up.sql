@@ -0,0 +1,4 @@
+-- Adds Pak'nSave as a supermarket.
+-- This is performed in its own migration, as a new enum value cannot be used
+-- in the same transaction that it is added in.
+ALTER TYPE supermarket ADD VALUE 'Pak''nSave';
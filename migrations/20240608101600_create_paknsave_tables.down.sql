@@ -0,0 +1,24 @@
+-- Revert creating Pak'nSave tables
+-- Any Pak'nSave stores must be removed before restoring the previous check
+DELETE FROM prices
+	WHERE store_id IN (SELECT id FROM stores WHERE supermarket = 'Pak''nSave');
+DELETE FROM stores
+	WHERE supermarket = 'Pak''nSave';
+
+ALTER TABLE stores
+	DROP CONSTRAINT chk_supermarket_type,
+	ADD CONSTRAINT chk_supermarket_type
+		CHECK (
+			(supermarket = 'Countdown' AND countdown_store_id IS NOT NULL)
+			OR (supermarket = 'New World' AND new_world_store_id IS NOT NULL)
+		),
+	DROP COLUMN paknsave_store_id;
+
+DROP TABLE paknsave_stores;
+
+ALTER TABLE products
+	RENAME CONSTRAINT fk_foodstuffs_product TO fk_new_world_product;
+ALTER TABLE products
+	RENAME COLUMN foodstuffs_id TO new_world_id;
+ALTER TABLE foodstuffs_products
+	RENAME TO new_world_products;
@@ -0,0 +1,29 @@
+-- Adds the tables required to track Pak'nSave stores.
+-- Pak'nSave and New World are both Foodstuffs brands which share product
+-- identifiers, so the New World products table is generalized to hold both.
+BEGIN;
+	ALTER TABLE new_world_products
+		RENAME TO foodstuffs_products;
+	ALTER TABLE products
+		RENAME COLUMN new_world_id TO foodstuffs_id;
+	ALTER TABLE products
+		RENAME CONSTRAINT fk_new_world_product TO fk_foodstuffs_product;
+
+	CREATE TABLE paknsave_stores (
+		id VARCHAR(36) PRIMARY KEY,
+		name VARCHAR(255) NOT NULL
+	);
+
+	ALTER TABLE stores
+		ADD COLUMN paknsave_store_id VARCHAR(36) UNIQUE,
+		ADD CONSTRAINT fk_paknsave_store_id
+			FOREIGN KEY(paknsave_store_id)
+				REFERENCES paknsave_stores(id),
+		DROP CONSTRAINT chk_supermarket_type,
+		ADD CONSTRAINT chk_supermarket_type
+			CHECK (
+				(supermarket = 'Countdown' AND countdown_store_id IS NOT NULL)
+				OR (supermarket = 'New World' AND new_world_store_id IS NOT NULL)
+				OR (supermarket = 'Pak''nSave' AND paknsave_store_id IS NOT NULL)
+			);
+COMMIT;
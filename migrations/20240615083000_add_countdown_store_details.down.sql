@@ -0,0 +1,6 @@
+-- Revert adding Countdown store details
+ALTER TABLE countdown_stores
+	DROP COLUMN address,
+	DROP COLUMN region,
+	DROP COLUMN latitude,
+	DROP COLUMN longitude;
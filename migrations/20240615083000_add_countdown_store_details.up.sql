@@ -0,0 +1,8 @@
+-- Adds the details retrieved when discovering Countdown stores.
+-- Stores saved before discovery was possible have no details, so all columns
+-- are nullable.
+ALTER TABLE countdown_stores
+	ADD COLUMN address VARCHAR(255),
+	ADD COLUMN region VARCHAR(255),
+	ADD COLUMN latitude DOUBLE PRECISION,
+	ADD COLUMN longitude DOUBLE PRECISION;
@@ -0,0 +1,4 @@
+-- Revert tracking original prices
+ALTER TABLE prices
+	DROP COLUMN original_cost_in_cents,
+	DROP COLUMN savings_in_cents;
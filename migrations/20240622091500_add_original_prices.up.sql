@@ -0,0 +1,8 @@
+-- Tracks the shelf price alongside the sale price of products, so that
+-- genuine price changes can be told apart from temporary specials.
+-- `cost_in_cents` remains the price the product was sold for.
+-- Prices recorded before this migration only know the sale price, so the new
+-- columns are nullable.
+ALTER TABLE prices
+	ADD COLUMN original_cost_in_cents INTEGER,
+	ADD COLUMN savings_in_cents INTEGER;
@@ -0,0 +1,2 @@
+-- Revert creating promotions table
+DROP TABLE promotions;
@@ -0,0 +1,13 @@
+-- Tracks promotions (such as multi-buys and club prices) of a price observation.
+CREATE TABLE promotions (
+	id SERIAL PRIMARY KEY,
+	price_id INTEGER NOT NULL,
+	promotion_type VARCHAR(32) NOT NULL,
+	quantity INTEGER NOT NULL,
+	price_in_cents INTEGER NOT NULL,
+	member_only BOOLEAN NOT NULL,
+
+	CONSTRAINT fk_price_id
+		FOREIGN KEY(price_id)
+			REFERENCES prices(id)
+);
@@ -0,0 +1,7 @@
+-- Revert tracking unit prices
+ALTER TABLE prices
+	DROP COLUMN unit_price_in_cents;
+
+ALTER TABLE countdown_products
+	DROP COLUMN package_size,
+	DROP COLUMN unit_of_measure;
@@ -0,0 +1,8 @@
+-- Tracks the package size of products and their unit price (e.g., $/kg), so
+-- products of differing sizes can be compared.
+ALTER TABLE countdown_products
+	ADD COLUMN package_size VARCHAR(32),
+	ADD COLUMN unit_of_measure VARCHAR(32);
+
+ALTER TABLE prices
+	ADD COLUMN unit_price_in_cents INTEGER;
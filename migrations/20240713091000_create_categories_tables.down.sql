@@ -0,0 +1,8 @@
+-- Revert creating categories tables
+DROP TABLE product_categories;
+DROP TABLE categories;
+
+ALTER TABLE foodstuffs_products
+	DROP COLUMN brand;
+ALTER TABLE countdown_products
+	DROP COLUMN brand;
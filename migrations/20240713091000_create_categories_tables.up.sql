@@ -0,0 +1,31 @@
+-- Tracks the brand of products, and which categories products belong to, so
+-- that category-level price indices can be produced.
+BEGIN;
+	ALTER TABLE countdown_products
+		ADD COLUMN brand VARCHAR(255);
+	ALTER TABLE foodstuffs_products
+		ADD COLUMN brand VARCHAR(255);
+
+	CREATE TABLE categories (
+		id SERIAL PRIMARY KEY,
+		supermarket supermarket NOT NULL,
+		name VARCHAR(255) NOT NULL,
+
+		UNIQUE (supermarket, name)
+	);
+
+	CREATE TABLE product_categories (
+		product_id INTEGER NOT NULL,
+		category_id INTEGER NOT NULL,
+
+		PRIMARY KEY (product_id, category_id),
+
+		CONSTRAINT fk_product_id
+			FOREIGN KEY(product_id)
+				REFERENCES products(id),
+
+		CONSTRAINT fk_category_id
+			FOREIGN KEY(category_id)
+				REFERENCES categories(id)
+	);
+COMMIT;
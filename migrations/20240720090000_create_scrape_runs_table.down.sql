@@ -0,0 +1,5 @@
+-- Revert creating scrape runs table
+ALTER TABLE prices
+	DROP COLUMN run_id;
+
+DROP TABLE scrape_runs;
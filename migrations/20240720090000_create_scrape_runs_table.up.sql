@@ -0,0 +1,27 @@
+-- Tracks each run of the scraper against a store, so that the prices of
+-- partial runs can be identified and excluded.
+BEGIN;
+	CREATE TABLE scrape_runs (
+		id SERIAL PRIMARY KEY,
+		supermarket supermarket NOT NULL,
+		store_id INTEGER NOT NULL,
+		started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+		finished_at TIMESTAMPTZ,
+		completed BOOLEAN NOT NULL DEFAULT FALSE,
+		product_count INTEGER,
+		error_count INTEGER NOT NULL DEFAULT 0,
+		error TEXT,
+		version VARCHAR(64) NOT NULL,
+
+		CONSTRAINT fk_store_id
+			FOREIGN KEY(store_id)
+				REFERENCES stores(id)
+	);
+
+	-- prices recorded before runs were tracked have no run
+	ALTER TABLE prices
+		ADD COLUMN run_id INTEGER,
+		ADD CONSTRAINT fk_run_id
+			FOREIGN KEY(run_id)
+				REFERENCES scrape_runs(id);
+COMMIT;
@@ -0,0 +1,2 @@
+-- Revert creating scrape run failures table
+DROP TABLE scrape_run_failures;
@@ -0,0 +1,13 @@
+-- Records the pages of products a scrape run failed to retrieve, so that
+-- misbehaving categories can be identified.
+CREATE TABLE scrape_run_failures (
+	id SERIAL PRIMARY KEY,
+	run_id INTEGER NOT NULL,
+	category VARCHAR(255) NOT NULL,
+	page BIGINT NOT NULL,
+	error TEXT NOT NULL,
+
+	CONSTRAINT fk_run_id
+		FOREIGN KEY(run_id)
+			REFERENCES scrape_runs(id)
+);
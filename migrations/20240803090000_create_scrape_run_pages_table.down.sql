@@ -0,0 +1,5 @@
+-- Revert creating scrape run pages table
+ALTER TABLE scrape_runs
+	DROP COLUMN store_key;
+
+DROP TABLE scrape_run_pages;
@@ -0,0 +1,21 @@
+-- Persists the pages each scrape run needs to retrieve, along with the
+-- products of the pages already retrieved, so an interrupted run can resume.
+BEGIN;
+	CREATE TABLE scrape_run_pages (
+		run_id INTEGER NOT NULL,
+		category VARCHAR(255) NOT NULL,
+		page BIGINT NOT NULL,
+		-- the products found on the page, or null if it is yet to be retrieved
+		products JSONB,
+
+		PRIMARY KEY (run_id, category, page),
+
+		CONSTRAINT fk_run_id
+			FOREIGN KEY(run_id)
+				REFERENCES scrape_runs(id)
+	);
+
+	-- the id the supermarket uses for the store, so the run can be resumed
+	ALTER TABLE scrape_runs
+		ADD COLUMN store_key VARCHAR(255);
+COMMIT;
@@ -0,0 +1,3 @@
+-- Revert adding scrape run drift
+ALTER TABLE scrape_runs
+	DROP COLUMN drift;
@@ -0,0 +1,4 @@
+-- Records the parts of the supermarket's responses that a scrape run did not
+-- recognise, so changes to the API can be noticed. Null if there were none.
+ALTER TABLE scrape_runs
+	ADD COLUMN drift JSONB;
@@ -0,0 +1,2 @@
+-- Revert creating latest prices table
+DROP TABLE latest_prices;
@@ -0,0 +1,37 @@
+-- Tracks the latest price of each product at each store, and when it was last
+-- seen, so that a price which has not changed need not be inserted again.
+-- Existing prices are backfilled with the latest price of each product.
+BEGIN;
+	CREATE TABLE latest_prices (
+		product_id INTEGER NOT NULL,
+		store_id INTEGER NOT NULL,
+		-- the price recorded when the product last changed price
+		price_id INTEGER NOT NULL,
+		last_seen TIMESTAMPTZ NOT NULL,
+		-- the scrape run that last saw the price, null for older prices
+		last_run_id INTEGER,
+
+		PRIMARY KEY (product_id, store_id),
+
+		CONSTRAINT fk_product
+			FOREIGN KEY(product_id)
+				REFERENCES products(id),
+
+		CONSTRAINT fk_store_id
+			FOREIGN KEY(store_id)
+				REFERENCES stores(id),
+
+		CONSTRAINT fk_price_id
+			FOREIGN KEY(price_id)
+				REFERENCES prices(id),
+
+		CONSTRAINT fk_last_run_id
+			FOREIGN KEY(last_run_id)
+				REFERENCES scrape_runs(id)
+	);
+
+	INSERT INTO latest_prices (product_id, store_id, price_id, last_seen, last_run_id)
+		SELECT DISTINCT ON (product_id, store_id) product_id, store_id, id, time, run_id
+		FROM prices
+		ORDER BY product_id, store_id, time DESC, id DESC;
+COMMIT;
@@ -0,0 +1,222 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use time::{macros::format_description, Date};
+
+use crate::{config::StoreSelection, scraper::PriceStorage, supermarket::Supermarket, GIT_VERSION};
+
+/// Tracks the prices of products at New Zealand supermarkets.
+#[derive(Debug, Parser)]
+#[command(name = "supermarket-tracker", version = GIT_VERSION)]
+pub struct Cli {
+    /// The TOML configuration file to read, which environment variables and
+    /// options override [default: supermarket-tracker.toml, if it exists]
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        env = "SUPERMARKET_TRACKER_CONFIG"
+    )]
+    pub config: Option<PathBuf>,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Scrapes the prices of every product at the stores of a supermarket.
+    Scrape(ScrapeArgs),
+    /// Lists the known stores of a supermarket, or discovers them.
+    Stores(StoresArgs),
+    /// Lists the products tracked for a supermarket.
+    Products(ProductsArgs),
+    /// Shows the recorded prices of a product.
+    Prices(PricesArgs),
+    /// Exports recorded prices as CSV.
+    Export(ExportArgs),
+    /// Scrapes supermarkets on the schedules of the configuration file, until
+    /// stopped with SIGTERM or Ctrl-C.
+    Daemon(DaemonArgs),
+    /// Applies any pending database migrations.
+    Migrate,
+    /// Generates shell completions, printing them to stdout.
+    Completions {
+        /// The shell to generate completions for.
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ScrapeArgs {
+    /// The supermarket to scrape.
+    #[arg(long, value_enum, ignore_case = true)]
+    pub supermarket: Supermarket,
+    /// A comma separated list of store ids or names to scrape, 'all' to
+    /// scrape every known store, or 'default' to scrape the largest store in
+    /// each island [default: default]
+    #[arg(long, env = "SUPERMARKET_STORES", value_parser = parse_store_selection)]
+    pub stores: Option<StoreSelection>,
+    /// Skips inserting products and prices into the database.
+    #[arg(long)]
+    pub no_insert: bool,
+    /// Which prices to insert: every price, or only those which changed since
+    /// the product was last seen at the store [default: every]
+    #[arg(long, value_enum, env = "PRICE_STORAGE")]
+    pub price_storage: Option<PriceStorage>,
+    #[command(flatten)]
+    pub retry: RetryArgs,
+    #[command(flatten)]
+    pub rate_limit: RateLimitArgs,
+    #[command(flatten)]
+    pub cache: CacheArgs,
+    /// Resumes an interrupted scrape run, only retrieving the pages it had
+    /// not yet retrieved.
+    #[arg(long, value_name = "RUN_ID", conflicts_with_all = ["replay", "reparse"])]
+    pub resume: Option<i32>,
+    /// Saves the products and prices of a cache file, instead of scraping.
+    #[arg(long, value_name = "PATH", conflicts_with = "reparse")]
+    pub replay: Option<PathBuf>,
+    /// Saves the products and prices re-parsed from an archive directory,
+    /// instead of scraping.
+    #[arg(long, value_name = "ARCHIVE")]
+    pub reparse: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct DaemonArgs {
+    /// Skips inserting products and prices into the database.
+    #[arg(long)]
+    pub no_insert: bool,
+    /// Which prices to insert: every price, or only those which changed since
+    /// the product was last seen at the store [default: every]
+    #[arg(long, value_enum, env = "PRICE_STORAGE")]
+    pub price_storage: Option<PriceStorage>,
+    #[command(flatten)]
+    pub retry: RetryArgs,
+    #[command(flatten)]
+    pub cache: CacheArgs,
+}
+
+/// Describes how requests that fail with a transient error are retried.
+#[derive(Debug, Clone, Args)]
+pub struct RetryArgs {
+    /// The amount of times to retry a request on timeouts, server errors or
+    /// rate limiting [default: 3]
+    #[arg(long, value_name = "COUNT", env = "HTTP_MAX_RETRIES")]
+    pub max_retries: Option<u32>,
+    /// The delay before the first retry, doubling (with jitter) after each
+    /// retry [default: 500]
+    #[arg(long, value_name = "MS", env = "HTTP_RETRY_DELAY_MS")]
+    pub retry_delay_ms: Option<u64>,
+}
+
+/// Overrides the rate limits of the supermarket.
+///
+/// Each option falls back to an environment variable of the same name
+/// prefixed by the supermarket, e.g., `COUNTDOWN_REQUESTS_PER_SECOND`.
+#[derive(Debug, Default, Args)]
+pub struct RateLimitArgs {
+    /// The rate to start requesting pages at, which adapts to rate limiting
+    /// [default: 4]
+    #[arg(long, value_name = "RATE")]
+    pub requests_per_second: Option<f64>,
+    /// The fastest rate to speed up to while requests succeed [default: 8, or
+    /// 12 for Foodstuffs]
+    #[arg(long, value_name = "RATE")]
+    pub max_requests_per_second: Option<f64>,
+    /// The amount of pages to request in parallel [default: 2, or 4 for
+    /// Foodstuffs]
+    #[arg(long, value_name = "COUNT")]
+    pub concurrent_requests: Option<usize>,
+}
+
+/// Describes where products are cached, and raw responses archived.
+#[derive(Debug, Clone, Args)]
+pub struct CacheArgs {
+    /// The directory to cache the products of each store in [default: cache]
+    #[arg(long, value_name = "DIRECTORY", env = "CACHE_DIRECTORY")]
+    pub cache_dir: Option<PathBuf>,
+    /// Compresses caches with gzip.
+    #[arg(
+        long,
+        value_name = "BOOL",
+        env = "CACHE_COMPRESS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub compress_cache: Option<bool>,
+    /// The amount of days to keep caches for, or 0 to keep them forever
+    /// [default: 30]
+    #[arg(long, value_name = "DAYS", env = "CACHE_RETENTION_DAYS")]
+    pub cache_retention_days: Option<u64>,
+    /// Archives the raw responses of each store into the directory.
+    #[arg(long, value_name = "DIRECTORY", env = "ARCHIVE_DIRECTORY")]
+    pub archive_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct StoresArgs {
+    /// The supermarket to list the stores of.
+    #[arg(long, value_enum, ignore_case = true)]
+    pub supermarket: Supermarket,
+    /// Discovers every pickup store of the supermarket and saves them, so
+    /// they can be scraped. Only required for Countdown.
+    #[arg(long)]
+    pub discover: bool,
+    /// Skips saving discovered stores into the database.
+    #[arg(long, requires = "discover")]
+    pub no_insert: bool,
+    #[command(flatten)]
+    pub retry: RetryArgs,
+    #[command(flatten)]
+    pub rate_limit: RateLimitArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ProductsArgs {
+    /// The supermarket to list the products of. New World and Pak'nSave
+    /// share their products.
+    #[arg(long, value_enum, ignore_case = true)]
+    pub supermarket: Supermarket,
+    /// Only lists products with a name containing the text.
+    #[arg(long)]
+    pub search: Option<String>,
+    /// The maximum amount of products to list.
+    #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(i64).range(1..))]
+    pub limit: i64,
+}
+
+#[derive(Debug, Args)]
+pub struct PricesArgs {
+    /// The id of the product, as listed by the `products` command.
+    pub product_id: i32,
+    /// Only shows prices recorded on or after the date, e.g., 2024-01-31.
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    pub since: Option<Date>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Only exports the prices of the supermarket.
+    #[arg(long, value_enum, ignore_case = true)]
+    pub supermarket: Option<Supermarket>,
+    /// Only exports prices recorded on or after the date, e.g., 2024-01-31.
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    pub since: Option<Date>,
+    /// The file to write the prices to, rather than stdout.
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+/// Parses the stores to scrape, as described by [`StoreSelection::parse`].
+fn parse_store_selection(value: &str) -> Result<StoreSelection, String> {
+    StoreSelection::parse(value)
+        .ok_or_else(|| "pass a comma separated list of store ids or names, or 'all'".to_string())
+}
+
+/// Parses a date in the `YYYY-MM-DD` format.
+fn parse_date(value: &str) -> Result<Date, String> {
+    Date::parse(value, format_description!("[year]-[month]-[day]"))
+        .map_err(|_| format!("'{value}' is not a date in the YYYY-MM-DD format"))
+}
@@ -0,0 +1,450 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use error_stack::{Report, Result, ResultExt};
+use secrecy::Secret;
+use serde::{de::Error, Deserialize, Deserializer};
+use sqlx::postgres::PgSslMode;
+
+use super::{ConfigError, StoreSelection};
+use crate::{scraper::PriceStorage, supermarket::Supermarket};
+
+/// The configuration file read when no `--config` option is passed, if it
+/// exists.
+pub const DEFAULT_CONFIG_PATH: &str = "supermarket-tracker.toml";
+
+/// The options of the TOML configuration file.
+///
+/// Every option is optional, and is overridden by the environment variable or
+/// command line option of the same purpose.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// How to connect to the Postgres database.
+    pub database: DatabaseTable,
+    /// How requests to the supermarkets are retried.
+    pub http: HttpTable,
+    /// Where products are cached, and raw responses archived.
+    pub cache: CacheTable,
+    /// What the application outputs.
+    pub output: OutputTable,
+    /// The stores and rate limits of Countdown.
+    pub countdown: SupermarketTable,
+    /// The stores and rate limits of New World.
+    pub new_world: SupermarketTable,
+    /// The stores and rate limits of Pak'nSave.
+    pub paknsave: SupermarketTable,
+    /// When the daemon scrapes each supermarket, from the `[[schedule]]`
+    /// tables.
+    #[serde(rename = "schedule")]
+    pub schedules: Vec<ScheduleTable>,
+}
+
+/// The `[database]` table, overridden by the `DATABASE_*` environment
+/// variables.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseTable {
+    /// A `postgres://` URL to connect with, whose parts the other options
+    /// override.
+    pub url: Option<Secret<String>>,
+    pub user: Option<String>,
+    pub password: Option<Secret<String>>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub name: Option<String>,
+    #[serde(deserialize_with = "deserialize_ssl_mode")]
+    pub ssl_mode: Option<PgSslMode>,
+    pub ssl_root_cert: Option<PathBuf>,
+    pub statement_timeout_ms: Option<u64>,
+    pub max_connections: Option<u32>,
+    pub min_connections: Option<u32>,
+    pub acquire_timeout_secs: Option<u64>,
+}
+
+/// The `[http]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpTable {
+    pub max_retries: Option<u32>,
+    pub retry_delay_ms: Option<u64>,
+}
+
+/// The `[cache]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheTable {
+    pub directory: Option<PathBuf>,
+    pub compress: Option<bool>,
+    pub retention_days: Option<u64>,
+    pub archive_directory: Option<PathBuf>,
+}
+
+/// The `[output]` table.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputTable {
+    /// If scraped products and prices are saved into the database.
+    pub insert: bool,
+    /// The filter of logs written to stderr, overridden by `RUST_LOG`.
+    pub log: String,
+    /// Which prices are inserted into the database.
+    pub price_storage: Option<PriceStorage>,
+}
+
+impl Default for OutputTable {
+    fn default() -> Self {
+        Self {
+            insert: true,
+            log: "info,supermarket_tracker=debug".to_string(),
+            price_storage: None,
+        }
+    }
+}
+
+/// The `[countdown]`, `[new_world]` and `[paknsave]` tables.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SupermarketTable {
+    /// The stores to scrape, either as a list of store ids or names, or as a
+    /// string accepted by `--stores`.
+    #[serde(deserialize_with = "deserialize_stores")]
+    pub stores: Option<StoreSelection>,
+    pub requests_per_second: Option<f64>,
+    pub max_requests_per_second: Option<f64>,
+    pub concurrent_requests: Option<usize>,
+}
+
+/// A `[[schedule]]` table, describing when the daemon scrapes a supermarket.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleTable {
+    /// The supermarket to scrape.
+    pub supermarket: Supermarket,
+    /// When to scrape the supermarket, in the local time zone.
+    #[serde(deserialize_with = "deserialize_cron")]
+    pub cron: cron::Schedule,
+    /// The stores to scrape, falling back to the stores of the table of the
+    /// supermarket.
+    #[serde(default, deserialize_with = "deserialize_stores")]
+    pub stores: Option<StoreSelection>,
+}
+
+impl ConfigFile {
+    /// Reads and validates the configuration file at `path`.
+    ///
+    /// Without a `path`, [`DEFAULT_CONFIG_PATH`] is read if it exists, and
+    /// otherwise the configuration is empty.
+    ///
+    /// # Errors
+    /// - If the file cannot be read.
+    /// - If the file is not valid TOML, or has an unknown option.
+    /// - If an option has an invalid value.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let default_path = Path::new(DEFAULT_CONFIG_PATH);
+        let path = match path {
+            Some(path) => path,
+            None if default_path.is_file() => default_path,
+            None => return Ok(Self::default()),
+        };
+
+        let contents = fs::read_to_string(path).change_context_lazy(|| ConfigError::ReadFile {
+            path: path.to_path_buf(),
+        })?;
+        let file: Self =
+            toml::from_str(&contents).change_context_lazy(|| ConfigError::InvalidFile {
+                path: path.to_path_buf(),
+            })?;
+
+        file.validate()
+            .attach_printable_lazy(|| format!("In {}", path.display()))?;
+
+        Ok(file)
+    }
+
+    /// The table of the `supermarket`.
+    #[must_use]
+    pub fn supermarket(&self, supermarket: &Supermarket) -> &SupermarketTable {
+        match supermarket {
+            Supermarket::Countdown => &self.countdown,
+            Supermarket::NewWorld => &self.new_world,
+            Supermarket::PakNSave => &self.paknsave,
+        }
+    }
+
+    /// Checks the values of the options that TOML types do not constrain.
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.database.max_connections == Some(0) {
+            return Err(invalid_option("database", "max_connections"))
+                .attach_printable("suggestion: at least one connection is required");
+        }
+
+        for (table, supermarket) in [
+            ("countdown", &self.countdown),
+            ("new_world", &self.new_world),
+            ("paknsave", &self.paknsave),
+        ] {
+            for (key, rate) in [
+                ("requests_per_second", supermarket.requests_per_second),
+                (
+                    "max_requests_per_second",
+                    supermarket.max_requests_per_second,
+                ),
+            ] {
+                if rate.is_some_and(|rate| rate <= 0.0) {
+                    return Err(invalid_option(table, key)).attach_printable(
+                        "suggestion: the requests per second must be greater than 0",
+                    );
+                }
+            }
+
+            if supermarket.concurrent_requests == Some(0) {
+                return Err(invalid_option(table, "concurrent_requests"))
+                    .attach_printable("suggestion: at least one concurrent request is required");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// An error for the invalid `key` of the `table`.
+fn invalid_option(table: &str, key: &str) -> Report<ConfigError> {
+    Report::new(ConfigError::InvalidOption {
+        option: format!("{table}.{key}"),
+    })
+}
+
+/// Deserializes the stores of a supermarket from either a list of store ids
+/// or names, or a string parsed by [`StoreSelection::parse`].
+fn deserialize_stores<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<StoreSelection>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stores {
+        List(Vec<String>),
+        Text(String),
+    }
+
+    let selection = match Stores::deserialize(deserializer)? {
+        Stores::List(stores) => {
+            let stores = stores
+                .into_iter()
+                .map(|store| store.trim().to_string())
+                .filter(|store| !store.is_empty())
+                .collect::<Vec<_>>();
+
+            (!stores.is_empty()).then_some(StoreSelection::Only(stores))
+        }
+        Stores::Text(value) => StoreSelection::parse(&value),
+    };
+
+    selection.map(Some).ok_or_else(|| {
+        D::Error::custom("expected a list of store ids or names, \"all\" or \"default\"")
+    })
+}
+
+/// Deserializes an SSL mode accepted by the `sslmode` parameter of Postgres.
+fn deserialize_ssl_mode<'de, D>(deserializer: D) -> std::result::Result<Option<PgSslMode>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(value) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    value.parse().map(Some).map_err(|_| {
+        D::Error::custom(
+            "expected one of \"disable\", \"allow\", \"prefer\", \"require\", \"verify-ca\" or \"verify-full\"",
+        )
+    })
+}
+
+/// Deserializes a cron expression, either with the standard five fields
+/// (minute, hour, day of month, month and day of week), or with a leading
+/// field of seconds, and optionally a trailing field of years.
+///
+/// The days of the week are always numbered as by standard cron, from 0 (or
+/// 7) for Sunday to 6 for Saturday, rather than as by the `cron` crate.
+fn deserialize_cron<'de, D>(deserializer: D) -> std::result::Result<cron::Schedule, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let expression = String::deserialize(deserializer)?;
+    let mut fields: Vec<_> = expression.split_whitespace().map(String::from).collect();
+    if fields.len() == 5 {
+        fields.insert(0, "0".to_string());
+    }
+    // otherwise an invalid number of fields is left for the `cron` crate to
+    // report
+    if matches!(fields.len(), 6 | 7) {
+        fields[5] = standard_days_of_week(&fields[5]);
+    }
+    let expression = fields.join(" ");
+
+    expression
+        .parse()
+        .map_err(|error| D::Error::custom(format!("invalid cron expression: {error}")))
+}
+
+/// Renumbers the days of a standard day of week field, from 0 (or 7) for
+/// Sunday, to those of the `cron` crate, from 1 for Sunday.
+///
+/// Names of days, and values that are not days, are left for the `cron` crate
+/// to parse.
+fn standard_days_of_week(field: &str) -> String {
+    field
+        .split(',')
+        .map(|item| {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, step.parse().ok()),
+                None => (item, Some(1)),
+            };
+            let (first, last) = match range.split_once('-') {
+                Some((first, last)) => (first.parse().ok(), last.parse().ok()),
+                None if range == "*" => (Some(0), Some(6)),
+                // a single day with a step repeats until the end of the week
+                None if item.contains('/') => (range.parse().ok(), Some(6)),
+                None => (range.parse().ok(), range.parse().ok()),
+            };
+
+            match (first, last, step) {
+                (Some(first), Some(last), Some(step)) if first <= last && last <= 7 && step > 0 => {
+                    // Sunday is both 0 and 7, so ranges ending on Sunday are
+                    // listed day by day
+                    (first..=last)
+                        .step_by(step)
+                        .map(|day: u8| (day % 7 + 1).to_string())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }
+                _ => item.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses and validates the contents of a configuration file.
+    fn parse(contents: &str) -> Result<ConfigFile, ConfigError> {
+        let file: ConfigFile =
+            toml::from_str(contents).change_context(ConfigError::InvalidFile {
+                path: PathBuf::from(DEFAULT_CONFIG_PATH),
+            })?;
+        file.validate()?;
+
+        Ok(file)
+    }
+
+    #[test]
+    fn accepts_an_empty_file() {
+        let file = parse("").unwrap();
+
+        assert!(file.output.insert);
+        assert!(file.schedules.is_empty());
+    }
+
+    #[test]
+    fn reads_stores_as_a_list_or_a_string() {
+        let file = parse(
+            r#"
+            [countdown]
+            stores = ["1906076", " Countdown Mt Eden "]
+
+            [new_world]
+            stores = "all"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.countdown.stores,
+            Some(StoreSelection::Only(vec![
+                "1906076".to_string(),
+                "Countdown Mt Eden".to_string()
+            ]))
+        );
+        assert_eq!(file.new_world.stores, Some(StoreSelection::All));
+        assert_eq!(file.paknsave.stores, None);
+    }
+
+    #[test]
+    fn rejects_rates_that_are_not_positive() {
+        for rate in ["0.0", "-1.5"] {
+            assert!(parse(&format!("[countdown]\nrequests_per_second = {rate}")).is_err());
+            assert!(parse(&format!("[paknsave]\nmax_requests_per_second = {rate}")).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        for contents in [
+            "[new_world]\nconcurrent_requests = 0",
+            "[new_world]\nconcurrent_requests = -1",
+            "[database]\nmax_connections = 0",
+            "[database]\nssl_mode = \"sometimes\"",
+            "[countdown]\nstores = []",
+            "[output]\nprice_storage = \"some\"",
+        ] {
+            assert!(parse(contents).is_err(), "{contents}");
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        for contents in [
+            "[countdown]\nrequests_per_minute = 60",
+            "[foodtown]\nstores = \"all\"",
+            "[[schedule]]\nsupermarket = \"Foodtown\"\ncron = \"0 7 * * *\"",
+        ] {
+            assert!(parse(contents).is_err(), "{contents}");
+        }
+    }
+
+    /// The weekdays of the next `count` times the `cron` expression is due,
+    /// from Monday the 6th of January 2025.
+    fn next_weekdays(cron: &str, count: usize) -> Vec<chrono::Weekday> {
+        use chrono::{Datelike, TimeZone, Utc};
+
+        let file = parse(&format!(
+            "[[schedule]]\nsupermarket = \"Countdown\"\ncron = \"{cron}\""
+        ))
+        .unwrap();
+        let start = Utc.with_ymd_and_hms(2025, 1, 6, 0, 0, 0).unwrap();
+
+        file.schedules[0]
+            .cron
+            .after(&start)
+            .take(count)
+            .map(|due| due.weekday())
+            .collect()
+    }
+
+    #[test]
+    fn numbers_days_of_the_week_from_sunday_as_zero() {
+        use chrono::Weekday::{Fri, Mon, Sat, Sun, Thu, Tue, Wed};
+
+        assert_eq!(
+            next_weekdays("0 7 * * 1-5", 7),
+            [Mon, Tue, Wed, Thu, Fri, Mon, Tue]
+        );
+        assert_eq!(next_weekdays("0 7 * * 0", 2), [Sun, Sun]);
+        assert_eq!(next_weekdays("0 7 * * 7", 2), [Sun, Sun]);
+        assert_eq!(next_weekdays("0 7 * * 5-7", 4), [Fri, Sat, Sun, Fri]);
+        assert_eq!(next_weekdays("0 7 * * */2", 5), [Tue, Thu, Sat, Sun, Tue]);
+        assert_eq!(next_weekdays("0 7 * * 1,Sat", 3), [Mon, Sat, Mon]);
+        // fields of seconds and years do not change the numbering
+        assert_eq!(next_weekdays("0 0 7 * * 1", 2), [Mon, Mon]);
+        assert_eq!(next_weekdays("0 0 7 * * 0 2025", 2), [Sun, Sun]);
+    }
+}
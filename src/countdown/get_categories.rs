@@ -4,6 +4,11 @@ use error_stack::{Context, Result, ResultExt};
 use reqwest::Client;
 use serde::Deserialize;
 
+use crate::{
+    retry::{retry, RetryConfig},
+    scraper::{ArchivedResponse, ResponseArchive},
+};
+
 use super::category::Category;
 
 /// API response for a specific navigation item
@@ -51,20 +56,44 @@ impl Context for GetCategoriesError {}
 
 /// Retrieves all the categories in the store.
 ///
+/// Retries the request according to `retry_config` on transient errors. The
+/// raw response is saved into `archive` before it is decoded.
+///
 /// # Errors
 /// - If unable to get the Countdown shell to use for API requests
 /// - If unable to decode the html countdown response
 pub async fn get_categories(
     client: &Client,
     base_url: &str,
+    retry_config: &RetryConfig,
+    archive: &ResponseArchive,
 ) -> Result<Vec<Category>, GetCategoriesError> {
-    let res: ShellResponse = client
-        .get(format!("{base_url}/shell"))
-        .send()
-        .await
-        .change_context(GetCategoriesError::HttpError)?
-        .json()
-        .await
+    let (url, body) = retry(retry_config, || async {
+        let response = client
+            .get(format!("{base_url}/shell"))
+            .send()
+            .await?
+            .error_for_status()?;
+        let url = response.url().to_string();
+
+        Ok((url, response.text().await?))
+    })
+    .await
+    .change_context(GetCategoriesError::HttpError)?;
+
+    let response = ArchivedResponse {
+        url,
+        category: None,
+        page: None,
+        body,
+    };
+    // the archive is only for auditing, so failing to write it should not
+    // prevent scraping
+    if let Err(report) = archive.save("shell", &response) {
+        tracing::warn!("Failed to archive the shell: {report:?}");
+    }
+
+    let res = serde_json::from_str::<ShellResponse>(&response.body)
         .change_context(GetCategoriesError::DecodeError)?;
 
     // read res.mainNavs[1]
@@ -0,0 +1,89 @@
+use error_stack::{Result, ResultExt};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::error::ApplicationError;
+
+/// A Countdown store which orders can be picked up from.
+#[derive(Debug)]
+pub struct PickupStore {
+    /// The address id of the store, used when setting the pickup location.
+    pub id: i32,
+    /// The name of the store.
+    pub name: String,
+    /// The street address of the store.
+    pub address: Option<String>,
+    /// The region the store is in, e.g., `Auckland`.
+    pub region: String,
+    /// The latitude of the store.
+    pub latitude: Option<f64>,
+    /// The longitude of the store.
+    pub longitude: Option<f64>,
+}
+
+/// Describes a single store in a [`StoreAreaResponse`].
+#[derive(Deserialize)]
+struct StoreAddressResponse {
+    id: i32,
+    name: String,
+    address: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
+/// Describes a region and all the stores within it.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoreAreaResponse {
+    /// The name of the region.
+    name: String,
+    store_addresses: Vec<StoreAddressResponse>,
+}
+
+/// Describes the response from the `/addresses/pickup-addresses` endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PickupAddressesResponse {
+    store_areas: Vec<StoreAreaResponse>,
+}
+
+/// Retrieves every Countdown store which offers pickup.
+///
+/// Uses the `/addresses/pickup-addresses` endpoint.
+///
+/// # Errors
+/// - If unable to perform the HTTP request
+/// - If unable to decode the JSON response
+#[tracing::instrument(name = "get pickup stores", level = "debug", skip_all)]
+pub async fn get_pickup_stores(
+    client: &Client,
+    base_url: &str,
+) -> Result<Vec<PickupStore>, ApplicationError> {
+    let res: PickupAddressesResponse = client
+        .get(format!("{base_url}/addresses/pickup-addresses"))
+        .send()
+        .await
+        .change_context(ApplicationError::HttpError)?
+        .json()
+        .await
+        .change_context(ApplicationError::StoreRetrieval)?;
+
+    let stores = res
+        .store_areas
+        .into_iter()
+        .flat_map(|area| {
+            area.store_addresses
+                .into_iter()
+                .map(move |store| PickupStore {
+                    id: store.id,
+                    name: store.name,
+                    address: store.address,
+                    region: area.name.clone(),
+                    latitude: store.latitude,
+                    longitude: store.longitude,
+                })
+        })
+        .collect();
+
+    Ok(stores)
+}
@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+
+use crate::error::ApplicationError;
+
+/// Retrieves every Countdown product stored in the database, as a map from
+/// the product's sku to its id in the `products` table.
+///
+/// # Errors
+/// If unable to retrieve the products from the database
+pub async fn get_product_ids(pool: &PgPool) -> Result<HashMap<String, i32>, ApplicationError> {
+    let product_ids = sqlx::query!(
+        r"SELECT products.id, countdown_products.sku FROM products
+			INNER JOIN countdown_products
+			ON products.countdown_id = countdown_products.id
+			WHERE countdown_id IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .change_context(ApplicationError::ProductRetrieval)?
+    .into_iter()
+    .map(|product| (product.sku, product.id))
+    .collect();
+
+    Ok(product_ids)
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     fmt,
     sync::Arc,
 };
@@ -10,7 +10,15 @@ use serde::Deserialize;
 use tokio::{sync::Mutex, task};
 use tracing::Span;
 
-use crate::{countdown::COUNTDOWN_BASE_URL, CONCURRENT_REQUESTS, PAGE_ITERATION_INTERVAL};
+use crate::{
+    error::ApplicationError,
+    rate_limiter::RateLimiter,
+    retry::{retry, RetryConfig},
+    scraper::{
+        to_cents, ArchivedResponse, DriftReport, FailedPage, PageQueue, Price, ProductFetch,
+        Promotion, PromotionKind, QueuedPage, ResponseArchive,
+    },
+};
 
 use super::{Category, Product};
 
@@ -26,32 +34,88 @@ struct ProductsResponse {
 #[serde(rename_all = "camelCase")]
 struct ProductsItemsResponse {
     /// All the items on the current page.
-    items: Vec<ItemResponse>,
+    ///
+    /// Items are decoded once their `type` is known, so an item of an unknown
+    /// type does not fail the whole page.
+    items: Vec<serde_json::Value>,
     /// The total amount of items across all pages.
     total_items: i32,
 }
 
+/// The `type` of items which are single products that can be purchased.
+const PRODUCT_ITEM_TYPE: &str = "Product";
+
+/// The `type` of items which are known to not be products, and are skipped.
+///
+/// - `PromoTile` is a promotional item
+/// - `PromotionalCarousel` is a carousel of products, usually contained
+///   within a group
+const NON_PRODUCT_ITEM_TYPES: [&str; 2] = ["PromoTile", "PromotionalCarousel"];
+
+/// The fields of a `/products` response which are always sent, but are not
+/// needed to track prices, so are not reported as drift.
+const IGNORED_FIELDS: &[&str] = &[
+    "action",
+    "breadcrumb",
+    "context",
+    "dasFacets",
+    "facets",
+    "isSuccessful",
+    "rootUrl",
+    "targetedOffersSection",
+    "products.items[].adId",
+    "products.items[].availabilityStatus",
+    "products.items[].averageWeightPerUnit",
+    "products.items[].eachUnitQuantity",
+    "products.items[].hasShopperNotes",
+    "products.items[].images",
+    "products.items[].quantity",
+    "products.items[].slug",
+    "products.items[].stockLevel",
+    "products.items[].subsAllowed",
+    "products.items[].supportsBothEachAndKgPricing",
+    "products.items[].unit",
+    "products.items[].variety",
+    "products.items[].price.averagePricePerSingleUnit",
+    "products.items[].price.canShowSavings",
+    "products.items[].price.discount",
+    "products.items[].price.hasBonusPoints",
+    "products.items[].price.isNew",
+    "products.items[].price.isSpecial",
+    "products.items[].price.isTargetedOffer",
+    "products.items[].price.total",
+    "products.items[].size.cupListPrice",
+    "products.items[].size.packageType",
+];
+
+/// An item of the `Product` type, which is a single product that can be
+/// purchased.
 #[derive(Deserialize)]
-#[serde(tag = "type")]
-enum ItemResponse {
-    /// A single product that can be purchased.
-    Product {
-        /// A lowercase string representation of the product
-        name: String,
-        /// The GS1 barcode.
-        barcode: String,
-        /// A unique store identifier ID.
-        sku: String,
-        /// The price of the product.
-        price: ProductPrice,
-    },
-    /// A promotional item
-    PromoTile {},
-    /// A carousel item of products, usually contained within a group.
-    PromotionalCarousel {},
+struct ProductItem {
+    /// The type of the item, which was already read to decide how to decode
+    /// the item.
+    #[serde(rename = "type")]
+    _kind: String,
+    /// A lowercase string representation of the product
+    name: String,
+    /// The GS1 barcode.
+    barcode: String,
+    /// A unique store identifier ID.
+    sku: String,
+    /// The brand of the product, e.g., `anchor`.
+    brand: Option<String>,
+    /// The departments the product belongs to.
+    #[serde(default)]
+    departments: Vec<Department>,
+    /// The price of the product.
+    price: ProductPrice,
+    /// The tag shown on the product, describing any promotions.
+    #[serde(rename = "productTag")]
+    product_tag: Option<ProductTag>,
+    /// The package size and unit price of the product.
+    size: Option<ProductSize>,
 }
 
-#[allow(dead_code)]
 #[derive(Deserialize)]
 struct ProductPrice {
     /// The normal price of the product.
@@ -65,6 +129,69 @@ struct ProductPrice {
     /// The total amount on sale by purchasing this product.
     #[serde(rename = "savePrice")]
     save: f32,
+    /// If the sale price is only available to Onecard members.
+    #[serde(rename = "isClubPrice", default)]
+    is_club_price: bool,
+}
+
+/// Describes a department a product belongs to.
+#[derive(Deserialize)]
+struct Department {
+    /// The name of the department, e.g., `Fruit & Veg`.
+    name: String,
+}
+
+/// Describes the size of a product.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ProductSize {
+    /// The price of one `cup_measure` of the product, in dollars.
+    ///
+    /// Named after the cup (comparative unit pricing) measure.
+    cup_price: Option<f32>,
+    /// The unit the cup price is measured in, e.g., `1kg`.
+    cup_measure: Option<String>,
+    /// The size of the package, e.g., `1.5kg`.
+    volume_size: Option<String>,
+}
+
+/// Describes the promotional tag of a product.
+#[derive(Deserialize)]
+struct ProductTag {
+    /// The multi-buy offer of the product, e.g., "2 for $5".
+    #[serde(rename = "multiBuy")]
+    multi_buy: Option<MultiBuy>,
+}
+
+#[derive(Deserialize)]
+struct MultiBuy {
+    /// The amount of the product that must be purchased.
+    quantity: i32,
+    /// The total price of purchasing `quantity` of the product, in dollars.
+    value: f32,
+}
+
+/// Computes the promotions of a product from its price and tag.
+fn get_promotions(price: &ProductPrice, product_tag: Option<ProductTag>) -> Vec<Promotion> {
+    if let Some(multi_buy) = product_tag.and_then(|tag| tag.multi_buy) {
+        return vec![Promotion {
+            kind: PromotionKind::MultiBuy,
+            quantity: multi_buy.quantity,
+            price: to_cents(multi_buy.value),
+            member_only: price.is_club_price,
+        }];
+    }
+
+    if price.is_club_price {
+        return vec![Promotion {
+            kind: PromotionKind::ClubPrice,
+            quantity: 1,
+            price: to_cents(price.sale),
+            member_only: true,
+        }];
+    }
+
+    Vec::new()
 }
 
 /// The size amount of items to query for each page.
@@ -82,11 +209,42 @@ pub struct GetProductResponse {
     ///
     /// Note that the API may not fully return all of these pages, it is an upper limit.
     pub total_pages: i64,
+    /// What was not recognised in the response.
+    pub drift: DriftReport,
+}
+
+#[derive(Debug)]
+#[allow(clippy::module_name_repetitions)]
+pub enum GetProductsError {
+    HttpError,
+    DecodeError,
+}
+
+impl fmt::Display for GetProductsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GetProductsError::HttpError => write!(f, "Failed to perform an HTTP request."),
+            GetProductsError::DecodeError => write!(
+                f,
+                "Failed to decode the JSON response to an appropriate type"
+            ),
+        }
+    }
 }
 
+impl Context for GetProductsError {}
+
 /// Retrieves a list of products
 ///
 /// Uses the `/products?target=browse` endpoint.
+///
+/// Waits for `rate_limiter` before each attempt, and retries the request
+/// according to `retry_config` on transient errors. The raw response is
+/// saved into `archive` before it is decoded.
+///
+/// # Errors
+/// - If unable to retrieve the page
+/// - If unable to decode the page
 #[tracing::instrument(
 	name = "get products",
 	level = "trace",
@@ -102,63 +260,158 @@ pub async fn get_products(
     base_url: &str,
     page_number: i64,
     category: &Category,
-) -> Result<GetProductResponse, reqwest::Error> {
+    retry_config: &RetryConfig,
+    rate_limiter: &RateLimiter,
+    archive: &ResponseArchive,
+) -> Result<GetProductResponse, GetProductsError> {
     // our Category contains url information
     // but we only want the last part of the url
-    let category_url_part = category.url.split('/').last();
-
-    let res: ProductsResponse = client
-        .get(format!("{base_url}/products"))
-        .query(&[
-            ("size", Some(PAGE_SIZE.to_string())),
-            ("target", Some(String::from("browse"))),
-            ("page", Some(page_number.to_string())),
-            (
-                "dasFilter",
-                category_url_part.map(|url| format!("Department;;{url};false")),
-            ),
-        ])
-        .send()
-        .await?
-        .json()
-        .await?;
-
-    let products = res
-        .products
-        .items
-        .into_iter()
-        .filter_map(|item| match item {
-            ItemResponse::Product {
-                name,
-                barcode,
-                price,
-                sku,
-                ..
-            } => Some(Product {
-                name,
-                barcode,
-                sku,
-                // convert to cents from dollars
-                #[allow(clippy::cast_possible_truncation)]
-                per_unit_price: (price.sale * 100.0).round() as i32,
-            }),
-            _ => None,
-        })
-        .collect::<HashSet<Product>>();
+    let category_url_part = category.url.split('/').next_back();
+
+    let (url, body) = retry(retry_config, || async {
+        let response = rate_limiter
+            .send(client.get(format!("{base_url}/products")).query(&[
+                ("size", Some(PAGE_SIZE.to_string())),
+                ("target", Some(String::from("browse"))),
+                ("page", Some(page_number.to_string())),
+                (
+                    "dasFilter",
+                    category_url_part.map(|url| format!("Department;;{url};false")),
+                ),
+            ]))
+            .await?;
+        let url = response.url().to_string();
+
+        Ok((url, response.text().await?))
+    })
+    .await
+    .change_context(GetProductsError::HttpError)?;
+
+    let response = ArchivedResponse {
+        url,
+        category: Some(category.name.clone()),
+        page: Some(page_number),
+        body,
+    };
+    // the archive is only for auditing, so failing to write it should not
+    // lose the products of the page
+    // named by the slug of the category, as names differing only in
+    // punctuation would be sanitized into the same file name
+    if let Err(report) = archive.save(
+        &format!(
+            "products-{}-{page_number:04}",
+            category_url_part.unwrap_or(&category.name)
+        ),
+        &response,
+    ) {
+        tracing::warn!(
+            "Failed to archive page {page_number} of '{}': {report:?}",
+            category.name
+        );
+    }
+
+    let res = parse_products(&response.body, page_number, &category.name)
+        .change_context(GetProductsError::DecodeError)?;
+
+    Span::current().record("product_count_retrieved", res.products.len());
+
+    Ok(res)
+}
+
+/// Decodes a page of products from the body of a `/products` response.
+///
+/// `category` is the name of the category the page was browsed from.
+///
+/// Items of unknown types are skipped, and along with any fields that are not
+/// decoded, are recorded in the returned [`DriftReport`].
+fn parse_products(
+    body: &str,
+    page_number: i64,
+    category: &str,
+) -> serde_json::Result<GetProductResponse> {
+    let mut drift = DriftReport::default();
+
+    let mut deserializer = serde_json::Deserializer::from_str(body);
+    let res: ProductsResponse = serde_ignored::deserialize(&mut deserializer, |path| {
+        drift.unknown_field("", &path, IGNORED_FIELDS);
+    })?;
+    deserializer.end()?;
+
+    let mut products = HashSet::new();
+    for item in res.products.items {
+        let kind = item
+            .get("type")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("<missing>")
+            .to_string();
+
+        if kind == PRODUCT_ITEM_TYPE {
+            let item: ProductItem = serde_ignored::deserialize(item, |path| {
+                drift.unknown_field("products.items[]", &path, IGNORED_FIELDS);
+            })?;
+            products.insert(to_product(item, category));
+        } else if !NON_PRODUCT_ITEM_TYPES.contains(&kind.as_str()) {
+            drift.unknown_type(&kind);
+        }
+    }
 
     let is_end =
         page_number * i64::from(PAGE_SIZE) > res.products.total_items.into() || products.is_empty();
 
-    Span::current().record("product_count_retrieved", products.len());
-
     Ok(GetProductResponse {
         products,
         next_page: (!is_end).then_some(page_number + 1),
         #[allow(clippy::cast_possible_truncation)]
         total_pages: (f64::from(res.products.total_items) / f64::from(PAGE_SIZE)).ceil() as i64,
+        drift,
     })
 }
 
+/// Converts a decoded product item into a [`Product`], which was found
+/// browsing the `category`.
+fn to_product(item: ProductItem, category: &str) -> Product {
+    let size = item.size.unwrap_or_default();
+    let categories = item
+        .departments
+        .into_iter()
+        .map(|department| department.name)
+        .chain([category.to_string()])
+        .collect();
+
+    Product {
+        name: item.name,
+        barcode: item.barcode,
+        sku: item.sku,
+        brand: item.brand.filter(|brand| !brand.is_empty()),
+        categories,
+        price: Price::from_dollars(item.price.original, item.price.sale, item.price.save),
+        promotions: get_promotions(&item.price, item.product_tag),
+        package_size: size.volume_size.filter(|size| !size.is_empty()),
+        unit_of_measure: size.cup_measure.filter(|measure| !measure.is_empty()),
+        // products without a unit price report a price of 0
+        unit_price: size
+            .cup_price
+            .filter(|cup_price| *cup_price > 0.0)
+            .map(to_cents),
+    }
+}
+
+/// Merges products that were found in multiple categories, keyed by their
+/// sku.
+fn merge_products(products: impl IntoIterator<Item = Product>) -> Vec<Product> {
+    let mut merged: HashMap<String, Product> = HashMap::new();
+    for product in products {
+        match merged.entry(product.sku.clone()) {
+            Entry::Occupied(mut entry) => entry.get_mut().merge(product),
+            Entry::Vacant(entry) => {
+                entry.insert(product);
+            }
+        }
+    }
+
+    merged.into_values().collect()
+}
+
 type AddTask = Option<Box<dyn FnOnce(&GetProductResponse) -> Vec<PageRequestTask> + Send + Sync>>;
 
 struct PageRequestTask {
@@ -167,11 +420,46 @@ struct PageRequestTask {
     add_tasks: AddTask,
 }
 
+impl PageRequestTask {
+    /// A task to retrieve the first page of a category, which adds tasks for
+    /// every other page of the category once retrieved.
+    fn first_page(category: Category) -> Self {
+        Self {
+            category: category.clone(),
+            page: 1,
+            add_tasks: Some(Box::new(move |products_response| {
+                (2..=products_response.total_pages)
+                    .map(|page| PageRequestTask {
+                        category: category.clone(),
+                        page,
+                        add_tasks: None,
+                    })
+                    .collect()
+            })),
+        }
+    }
+
+    /// The page of the task, as tracked by a [`PageQueue`].
+    fn queued_page(&self) -> QueuedPage {
+        QueuedPage {
+            category: self.category.name.clone(),
+            page: self.page,
+        }
+    }
+}
+
 async fn perform_task(
     client: Client,
+    base_url: String,
     tasks: Arc<Mutex<VecDeque<PageRequestTask>>>,
-) -> Result<HashSet<Product>, reqwest::Error> {
-    let mut total_products = HashSet::new();
+    retry_config: RetryConfig,
+    rate_limiter: RateLimiter,
+    queue: PageQueue,
+    archive: ResponseArchive,
+) -> Result<ProductFetch<Product>, ApplicationError> {
+    let mut total_products = Vec::new();
+    let mut failed_pages = Vec::new();
+    let mut drift = DriftReport::default();
 
     loop {
         let task = tasks.lock().await.pop_front();
@@ -179,27 +467,70 @@ async fn perform_task(
             break;
         };
 
-        let res = get_products(&client, COUNTDOWN_BASE_URL, task.page, &task.category).await?;
+        let res = get_products(
+            &client,
+            &base_url,
+            task.page,
+            &task.category,
+            &retry_config,
+            &rate_limiter,
+            &archive,
+        )
+        .await;
+
+        let res = match res {
+            Ok(res) => res,
+            Err(error) => {
+                // skip the page, so one misbehaving category does not lose
+                // the prices of every other category
+                tracing::warn!(
+                    "Failed to retrieve page {} of '{}': {error:?}",
+                    task.page,
+                    task.category
+                );
+                failed_pages.push(FailedPage {
+                    category: task.category.name,
+                    page: task.page,
+                    error: format!("{error:?}"),
+                });
+                continue;
+            }
+        };
 
         // handle the add_tasks callback if it existed
+        // the new pages are queued before this page is completed, so they
+        // are not lost if the run is interrupted
+        let queued_page = task.queued_page();
         if let Some(callback) = task.add_tasks {
             let new_tasks = callback(&res);
+            queue
+                .enqueue(
+                    &new_tasks
+                        .iter()
+                        .map(PageRequestTask::queued_page)
+                        .collect::<Vec<_>>(),
+                )
+                .await?;
             tasks.lock().await.extend(new_tasks);
         }
 
-        total_products.extend(res.products);
-
-        // give the API some time to rest
-        // so we don't get rate limited
-        tokio::time::sleep(PAGE_ITERATION_INTERVAL).await;
+        drift.merge(res.drift);
+        let products = res.products.into_iter().collect::<Vec<_>>();
+        queue.complete(&queued_page, &products).await?;
+        total_products.extend(products);
     }
 
-    Ok(total_products)
+    Ok(ProductFetch {
+        products: total_products,
+        failed_pages,
+        drift,
+    })
 }
 
 #[derive(Debug)]
 pub enum ProductRetrievalError {
     Join,
+    Queue,
     ProductRetrieval,
 }
 
@@ -207,7 +538,10 @@ impl fmt::Display for ProductRetrievalError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ProductRetrievalError::Join => write!(f, "Failed to join all products after querying"),
-            ProductRetrievalError::ProductRetrieval => write!(f, "Failed to retrieve a product"),
+            ProductRetrievalError::Queue => write!(f, "Failed to track the pages to retrieve"),
+            ProductRetrievalError::ProductRetrieval => {
+                write!(f, "Failed to retrieve any products")
+            }
         }
     }
 }
@@ -216,52 +550,311 @@ impl Context for ProductRetrievalError {}
 
 /// Retrieves all the products for a given [`Category`].
 ///
-/// Yields for [`PAGE_ITERATION_INTERVAL`] between requests, to prevent rate-limiting.
+/// Requests are shared between [`RateLimiter::concurrent_requests`] workers,
+/// which wait for `rate_limiter` before each request to prevent
+/// rate-limiting. Each page is retried according to `retry_config`, and pages
+/// which still fail are skipped and reported in the returned [`ProductFetch`].
 ///
-/// Runs [`CONCURRENT_REQUESTS`] requests at once.
+/// Pages are tracked in the `queue`. If the queue has progress from a
+/// previous attempt, only its pending pages are retrieved. Every page
+/// retrieved is saved into `archive`.
+///
+/// # Errors
+/// - If unable to join the workers
+/// - If unable to track the pages in the queue
+/// - If every page failed to be retrieved
 #[tracing::instrument(name = "get_all_products", skip_all, fields(
 	num_categories = %categories.len()
 ))]
 pub async fn get_all_products(
     client: &reqwest::Client,
+    base_url: &str,
     categories: Vec<Category>,
-) -> Result<HashSet<Product>, ProductRetrievalError> {
-    let tasks: Arc<Mutex<VecDeque<PageRequestTask>>> = Arc::new(Mutex::new(
-        categories
+    retry_config: &RetryConfig,
+    rate_limiter: &RateLimiter,
+    queue: &PageQueue,
+    archive: &ResponseArchive,
+) -> Result<ProductFetch<Product>, ProductRetrievalError> {
+    let progress = queue
+        .progress::<Product>()
+        .await
+        .change_context(ProductRetrievalError::Queue)?;
+
+    let (initial_tasks, resumed_products) = if let Some(progress) = progress {
+        tracing::info!(
+            "Resuming with {} pending pages, and {} products already retrieved",
+            progress.pending.len(),
+            progress.products.len()
+        );
+
+        let categories = categories
             .into_iter()
-            .map(|category| PageRequestTask {
-                category: category.clone(),
-                page: 1,
-                add_tasks: Some(Box::new(move |products_response| {
-                    (2..=products_response.total_pages)
-                        .map(|page| PageRequestTask {
-                            category: category.clone(),
-                            page,
-                            add_tasks: None,
-                        })
-                        .collect()
-                })),
+            .map(|category| (category.name.clone(), category))
+            .collect::<HashMap<_, _>>();
+        let tasks = progress
+            .pending
+            .into_iter()
+            .filter_map(|pending| {
+                let Some(category) = categories.get(&pending.category) else {
+                    tracing::warn!("Skipping '{}' as it no longer exists", pending.category);
+                    return None;
+                };
+
+                // the first page is yet to find how many pages there are
+                Some(if pending.page == 1 {
+                    PageRequestTask::first_page(category.clone())
+                } else {
+                    PageRequestTask {
+                        category: category.clone(),
+                        page: pending.page,
+                        add_tasks: None,
+                    }
+                })
             })
-            .collect(),
-    ));
-
-    let task_results = futures::future::join_all((0..CONCURRENT_REQUESTS).map(|_| {
-        let new_client = client.clone();
-        let new_tasks = tasks.clone();
-        task::spawn(async move { perform_task(new_client, new_tasks).await })
-    }))
-    .await
-    .into_iter()
-    .collect::<std::result::Result<Vec<_>, _>>()
-    .change_context(ProductRetrievalError::Join)?;
+            .collect::<VecDeque<_>>();
 
-    let product_results = task_results
+        (tasks, progress.products)
+    } else {
+        let tasks = categories
+            .into_iter()
+            .map(PageRequestTask::first_page)
+            .collect::<VecDeque<_>>();
+        queue
+            .enqueue(
+                &tasks
+                    .iter()
+                    .map(PageRequestTask::queued_page)
+                    .collect::<Vec<_>>(),
+            )
+            .await
+            .change_context(ProductRetrievalError::Queue)?;
+
+        (tasks, Vec::new())
+    };
+    let tasks = Arc::new(Mutex::new(initial_tasks));
+
+    let task_results =
+        futures::future::join_all((0..rate_limiter.concurrent_requests()).map(|_| {
+            let new_client = client.clone();
+            let base_url = base_url.to_string();
+            let new_tasks = tasks.clone();
+            let retry_config = *retry_config;
+            let rate_limiter = rate_limiter.clone();
+            let queue = queue.clone();
+            let archive = archive.clone();
+            task::spawn(async move {
+                perform_task(
+                    new_client,
+                    base_url,
+                    new_tasks,
+                    retry_config,
+                    rate_limiter,
+                    queue,
+                    archive,
+                )
+                .await
+            })
+        }))
+        .await
         .into_iter()
         .collect::<std::result::Result<Vec<_>, _>>()
-        .change_context(ProductRetrievalError::ProductRetrieval)?
+        .change_context(ProductRetrievalError::Join)?
         .into_iter()
-        .flatten()
-        .collect();
+        .collect::<Result<Vec<_>, _>>()
+        .change_context(ProductRetrievalError::Queue)?;
+
+    let mut failed_pages = Vec::new();
+    let mut drift = DriftReport::default();
+    let mut products = resumed_products;
+    for task_result in task_results {
+        failed_pages.extend(task_result.failed_pages);
+        drift.merge(task_result.drift);
+        products.extend(task_result.products);
+    }
+
+    // products may be found in multiple categories, so we merge them
+    let products = merge_products(products);
+    if products.is_empty() && !failed_pages.is_empty() {
+        return Err(ProductRetrievalError::ProductRetrieval).attach_printable(format!(
+            "All {} pages failed, the first with {}",
+            failed_pages.len(),
+            failed_pages[0]
+        ));
+    }
+
+    Ok(ProductFetch {
+        products,
+        failed_pages,
+        drift,
+    })
+}
+
+/// Rebuilds the products of a store from the raw `/products` responses of an
+/// archive, using the current decoders.
+///
+/// Responses which are not pages of products, such as the shell, are
+/// skipped. Pages which can no longer be decoded are reported in the
+/// returned [`ProductFetch`].
+///
+/// # Errors
+/// If every page failed to be decoded.
+pub fn parse_archived_products(
+    responses: &[ArchivedResponse],
+) -> Result<ProductFetch<Product>, ProductRetrievalError> {
+    let mut products = Vec::new();
+    let mut failed_pages = Vec::new();
+    let mut drift = DriftReport::default();
+
+    for response in responses {
+        let (Some(category), Some(page)) = (&response.category, response.page) else {
+            continue;
+        };
+
+        match parse_products(&response.body, page, category) {
+            Ok(res) => {
+                drift.merge(res.drift);
+                products.extend(res.products);
+            }
+            Err(error) => {
+                tracing::warn!("Failed to decode page {page} of '{category}': {error}");
+                failed_pages.push(FailedPage {
+                    category: category.clone(),
+                    page,
+                    error: error.to_string(),
+                });
+            }
+        }
+    }
+
+    let products = merge_products(products);
+    if products.is_empty() && !failed_pages.is_empty() {
+        return Err(ProductRetrievalError::ProductRetrieval).attach_printable(format!(
+            "All {} pages failed to decode, the first with {}",
+            failed_pages.len(),
+            failed_pages[0]
+        ));
+    }
+
+    Ok(ProductFetch {
+        products,
+        failed_pages,
+        drift,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRUIT_VEG_FIRST_PAGE: &str =
+        include_str!("../../tests/fixtures/countdown/products/fruit-veg/1.json");
+    const FRUIT_VEG_LAST_PAGE: &str =
+        include_str!("../../tests/fixtures/countdown/products/fruit-veg/2.json");
+    const DRIFTED_PAGE: &str = include_str!("../../tests/fixtures/countdown/drift/products.json");
+
+    fn find<'a>(res: &'a GetProductResponse, sku: &str) -> &'a Product {
+        res.products
+            .iter()
+            .find(|product| product.sku == sku)
+            .unwrap_or_else(|| panic!("Expected a product with sku {sku}"))
+    }
+
+    #[test]
+    fn decodes_every_product_of_a_page() {
+        let res = parse_products(FRUIT_VEG_FIRST_PAGE, 1, "Fruit & Veg").unwrap();
+
+        assert_eq!(res.products.len(), 2);
+        assert_eq!(res.next_page, Some(2));
+        assert_eq!(res.total_pages, 2);
+        assert!(res.drift.is_empty(), "{}", res.drift);
+
+        let apples = find(&res, "281123");
+        assert_eq!(apples.brand, None);
+        assert_eq!(
+            apples.price,
+            Price {
+                original: 499,
+                sale: 399,
+                savings: 100,
+            }
+        );
+        assert_eq!(apples.unit_price, Some(399));
+        assert_eq!(apples.unit_of_measure.as_deref(), Some("1kg"));
+        assert_eq!(apples.package_size, None);
+        assert!(apples.promotions.is_empty());
+
+        let bananas = find(&res, "133211");
+        assert_eq!(
+            bananas.promotions,
+            [Promotion {
+                kind: PromotionKind::MultiBuy,
+                quantity: 2,
+                price: 600,
+                member_only: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn decodes_the_last_page() {
+        let res = parse_products(FRUIT_VEG_LAST_PAGE, 2, "Fruit & Veg").unwrap();
+
+        assert_eq!(res.next_page, None);
+
+        let carrots = find(&res, "135344");
+        assert_eq!(carrots.brand.as_deref(), Some("countdown"));
+        assert_eq!(carrots.package_size.as_deref(), Some("1.5kg"));
+        assert_eq!(
+            carrots.promotions,
+            [Promotion {
+                kind: PromotionKind::ClubPrice,
+                quantity: 1,
+                price: 200,
+                member_only: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn adds_the_browsed_category() {
+        let res = parse_products(FRUIT_VEG_FIRST_PAGE, 1, "Specials").unwrap();
+
+        let apples = find(&res, "281123");
+        assert_eq!(
+            apples.categories.iter().collect::<Vec<_>>(),
+            ["Fruit & Veg", "Specials"]
+        );
+    }
 
-    Ok(product_results)
+    #[test]
+    fn skips_and_counts_unknown_item_types() {
+        let res = parse_products(DRIFTED_PAGE, 1, "Fruit & Veg").unwrap();
+
+        assert_eq!(res.products.len(), 2);
+        assert_eq!(
+            res.drift.unknown_types.into_iter().collect::<Vec<_>>(),
+            [("Recipe".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn reports_unexpected_fields() {
+        let res = parse_products(DRIFTED_PAGE, 1, "Fruit & Veg").unwrap();
+
+        assert_eq!(
+            res.drift.unknown_fields.into_iter().collect::<Vec<_>>(),
+            [
+                ("products.items[].price.memberPrice".to_string(), 1),
+                ("recommendations".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn fails_on_a_product_missing_a_required_field() {
+        let body =
+            r#"{"products": {"items": [{"type": "Product", "name": "bread"}], "totalItems": 1}}"#;
+
+        assert!(parse_products(body, 1, "Bakery").is_err());
+    }
 }
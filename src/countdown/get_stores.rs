@@ -0,0 +1,42 @@
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+
+use crate::error::ApplicationError;
+
+use super::Store;
+
+/// Stores which are always known, even if they have not yet been saved to
+/// the database.
+///
+/// North Island and South Island have differing prices, so we know of the
+/// largest countdowns in each island.
+const BUILT_IN_STORES: [(i32, &str); 2] = [
+    (1_906_076, "Countdown Mt Eden"),
+    (1_352_617, "Woolworths Hornby"),
+];
+
+/// Retrieves all the known Countdown stores.
+///
+/// Countdown stores are known if they have previously been saved to the
+/// `countdown_stores` table, or are one of the built-in stores.
+///
+/// # Errors
+/// If unable to retrieve the stores from the database.
+#[tracing::instrument(name = "get stores", level = "debug", skip_all)]
+pub async fn get_stores(pool: &PgPool) -> Result<Vec<Store>, ApplicationError> {
+    let mut stores = sqlx::query_as!(Store, "SELECT id, name FROM countdown_stores ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .change_context(ApplicationError::StoreRetrieval)?;
+
+    for (id, name) in BUILT_IN_STORES {
+        if !stores.iter().any(|store| store.id == id) {
+            stores.push(Store {
+                id,
+                name: name.to_string(),
+            });
+        }
+    }
+
+    Ok(stores)
+}
@@ -1,26 +1,32 @@
 mod category;
 mod get_categories;
-mod get_off_sale_skus;
+mod get_pickup_stores;
+mod get_product_ids;
 mod get_products;
+mod get_stores;
 mod product;
-mod run;
-mod save_prices;
+mod save_pickup_stores;
 mod save_products;
 mod save_store;
+mod scraper;
 mod set_fulfillment_method;
 mod set_location;
+mod store;
 
 pub use category::Category;
 pub use get_categories::get_categories;
-pub use get_off_sale_skus::get_off_sale_skus;
-pub use get_products::{get_all_products, get_products};
+pub use get_pickup_stores::{get_pickup_stores, PickupStore};
+pub use get_product_ids::get_product_ids;
+pub use get_products::{get_all_products, get_products, parse_archived_products};
+pub use get_stores::get_stores;
 pub use product::Product;
-pub use run::run;
-pub use save_prices::save_prices;
+pub use save_pickup_stores::save_pickup_stores;
 pub use save_products::save_products;
 pub use save_store::save_store;
+pub use scraper::CountdownScraper;
 pub use set_fulfillment_method::set_fulfillment_method;
 pub use set_location::set_location;
+pub use store::Store;
 
 pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/105.0.0.0 Safari/537.36";
 pub const COUNTDOWN_BASE_URL: &str = "https://www.countdown.co.nz/api/v1";
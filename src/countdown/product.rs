@@ -1,11 +1,15 @@
-use serde::Serialize;
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::scraper::{Price, Promotion, ScrapedProduct};
 
 /// Represents a product that can be purchased from Countdown.
 ///
 /// We define two products to be equal if their `sku` values are equal.
 /// Similarly, we define the hash of a product to be solely from its sku,
 /// and not from any other field.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Product {
     /// Then name of the product.
     pub name: String,
@@ -13,8 +17,20 @@ pub struct Product {
     pub barcode: String,
     /// The sku of the product.
     pub sku: String,
-    /// The current price of the product, in cents.
-    pub per_unit_price: i32,
+    /// The brand of the product, e.g., `anchor`.
+    pub brand: Option<String>,
+    /// The names of the categories (and departments) the product was found in.
+    pub categories: BTreeSet<String>,
+    /// The current price of the product.
+    pub price: Price,
+    /// The promotions currently available for the product.
+    pub promotions: Vec<Promotion>,
+    /// The size of the package, e.g., `1.5kg`.
+    pub package_size: Option<String>,
+    /// The unit the unit price is measured in, e.g., `1kg`.
+    pub unit_of_measure: Option<String>,
+    /// The current price of one `unit_of_measure` of the product, in cents.
+    pub unit_price: Option<i32>,
 }
 
 impl Eq for Product {}
@@ -24,8 +40,42 @@ impl PartialEq for Product {
     }
 }
 
+impl Product {
+    /// Merges the categories of another instance of the same product into
+    /// this product.
+    pub fn merge(&mut self, other: Product) {
+        self.categories.extend(other.categories);
+    }
+}
+
 impl std::hash::Hash for Product {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         self.sku.hash(state);
     }
 }
+
+impl ScrapedProduct for Product {
+    fn key(&self) -> &str {
+        &self.sku
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn categories(&self) -> &BTreeSet<String> {
+        &self.categories
+    }
+
+    fn price(&self) -> Price {
+        self.price
+    }
+
+    fn promotions(&self) -> &[Promotion] {
+        &self.promotions
+    }
+
+    fn unit_price(&self) -> Option<i32> {
+        self.unit_price
+    }
+}
@@ -0,0 +1,64 @@
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+
+use crate::error::ApplicationError;
+
+use super::PickupStore;
+
+/// Saves discovered pickup stores into the `countdown_stores` table.
+///
+/// If a store already exists, its name and details are updated.
+#[tracing::instrument(name = "save pickup stores", level = "debug", skip_all, fields(
+	store_count = %stores.len()
+))]
+pub async fn save_pickup_stores(
+    pool: &PgPool,
+    stores: &[PickupStore],
+) -> Result<(), ApplicationError> {
+    let mut ids = Vec::with_capacity(stores.len());
+    let mut names = Vec::with_capacity(stores.len());
+    let mut addresses = Vec::with_capacity(stores.len());
+    let mut regions = Vec::with_capacity(stores.len());
+    let mut latitudes = Vec::with_capacity(stores.len());
+    let mut longitudes = Vec::with_capacity(stores.len());
+
+    for store in stores {
+        ids.push(store.id);
+        names.push(store.name.clone());
+        addresses.push(store.address.clone());
+        regions.push(store.region.clone());
+        latitudes.push(store.latitude);
+        longitudes.push(store.longitude);
+    }
+
+    sqlx::query!(
+        r#"
+		INSERT INTO countdown_stores (id, name, address, region, latitude, longitude)
+			SELECT * FROM UNNEST (
+				$1::integer[],
+				$2::text[],
+				$3::text[],
+				$4::text[],
+				$5::float8[],
+				$6::float8[]
+			)
+			ON CONFLICT (id) DO UPDATE SET
+				name = EXCLUDED.name,
+				address = EXCLUDED.address,
+				region = EXCLUDED.region,
+				latitude = EXCLUDED.latitude,
+				longitude = EXCLUDED.longitude
+		"#,
+        &ids[..],
+        &names[..],
+        &addresses[..] as &[Option<String>],
+        &regions[..],
+        &latitudes[..] as &[Option<f64>],
+        &longitudes[..] as &[Option<f64>]
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::SaveStore)?;
+
+    Ok(())
+}
@@ -8,34 +8,72 @@ use super::Product;
 
 /// Saves new products into the database.
 ///
-/// If a product already exists in the database (by SKU), no change is performed.
+/// If a product already exists in the database (by SKU), only its brand,
+/// package size and unit of measure are updated.
 ///
 /// Returns the new products that have not previously been tracked in the database.
 #[tracing::instrument(name = "save products", level = "debug", skip_all, fields(
 	product_count = %products.len()
 ))]
-pub async fn save_products(pool: &PgPool, products: Vec<Product>) -> Result<(), ApplicationError> {
+pub async fn save_products(pool: &PgPool, products: &[Product]) -> Result<(), ApplicationError> {
     let mut names = Vec::with_capacity(products.len());
     let mut barcodes = Vec::with_capacity(products.len());
     let mut skus = Vec::with_capacity(products.len());
+    let mut package_sizes = Vec::with_capacity(products.len());
+    let mut units_of_measure = Vec::with_capacity(products.len());
+    let mut brands = Vec::with_capacity(products.len());
 
     for product in products {
-        names.push(product.name);
-        barcodes.push(product.barcode);
-        skus.push(product.sku);
+        names.push(product.name.clone());
+        barcodes.push(product.barcode.clone());
+        skus.push(product.sku.clone());
+        package_sizes.push(product.package_size.clone());
+        units_of_measure.push(product.unit_of_measure.clone());
+        brands.push(product.brand.clone());
     }
 
+    // update the details of existing products, as retailers may resize or
+    // rebrand products
+    sqlx::query!(
+        r#"
+		UPDATE countdown_products SET
+			package_size = data.package_size,
+			unit_of_measure = data.unit_of_measure,
+			brand = data.brand
+		FROM UNNEST ($1::text[], $2::text[], $3::text[], $4::text[])
+			AS data(sku, package_size, unit_of_measure, brand)
+		WHERE countdown_products.sku = data.sku
+			AND (
+				countdown_products.package_size IS DISTINCT FROM data.package_size
+				OR countdown_products.unit_of_measure IS DISTINCT FROM data.unit_of_measure
+				OR countdown_products.brand IS DISTINCT FROM data.brand
+			)
+		"#,
+        &skus[..],
+        &package_sizes[..] as &[Option<String>],
+        &units_of_measure[..] as &[Option<String>],
+        &brands[..] as &[Option<String>]
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::NewProductsInsertionError)?;
+
     // insert into `countdown_products` table
     let new_countdown_products = sqlx::query!(
         r#"
-		INSERT INTO countdown_products (name, barcode, sku)
-			SELECT * FROM UNNEST ($1::text[], $2::text[], $3::text[])
+		INSERT INTO countdown_products (name, barcode, sku, package_size, unit_of_measure, brand)
+			SELECT * FROM UNNEST (
+				$1::text[], $2::text[], $3::text[], $4::text[], $5::text[], $6::text[]
+			)
 			ON CONFLICT (sku) DO NOTHING
 			RETURNING sku, id
 		"#,
         &names[..],
         &barcodes[..],
-        &skus[..]
+        &skus[..],
+        &package_sizes[..] as &[Option<String>],
+        &units_of_measure[..] as &[Option<String>],
+        &brands[..] as &[Option<String>]
     )
     .fetch_all(pool)
     .await
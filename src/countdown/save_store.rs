@@ -3,6 +3,9 @@ use sqlx::PgPool;
 
 /// Saves a Countdown store into the database.
 ///
+/// The store may have already been saved into `countdown_stores` by store
+/// discovery, in which case only the matching `stores` row is created.
+///
 /// If the store already exists, returns the store id.
 #[tracing::instrument(
 	name = "save_store",
@@ -14,31 +17,31 @@ use sqlx::PgPool;
 	)
 )]
 pub async fn save_store(pool: &PgPool, id: i32, name: String) -> Result<i32, sqlx::Error> {
-    let countdown_store_id = sqlx::query!(
+    sqlx::query!(
         r#"INSERT INTO countdown_stores (
 			id, name ) VALUES (
 				$1, $2
-			) ON CONFLICT DO NOTHING
-			RETURNING id"#,
+			) ON CONFLICT DO NOTHING"#,
         id,
         name,
     )
-    .fetch_optional(pool)
+    .execute(pool)
     .await?;
 
-    if countdown_store_id.is_some() {
-        // insert and return new store id
-        let id = sqlx::query!(
-            "INSERT INTO stores (
+    // insert and return new store id
+    let store_id = sqlx::query!(
+        "INSERT INTO stores (
 supermarket, countdown_store_id
 ) VALUES (
 'Countdown', $1
-) RETURNING id",
-            id
-        )
-        .fetch_one(pool)
-        .await?;
-        return Ok(id.id);
+) ON CONFLICT (countdown_store_id) DO NOTHING
+RETURNING id",
+        id
+    )
+    .fetch_optional(pool)
+    .await?;
+    if let Some(store_id) = store_id {
+        return Ok(store_id.id);
     }
 
     // return the existing store id
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use error_stack::{Result, ResultExt};
+use reqwest::Client;
+use sqlx::PgPool;
+
+use crate::{
+    error::ApplicationError,
+    rate_limiter::{RateLimitConfig, RateLimiter},
+    retry::RetryConfig,
+    scraper::{ArchivedResponse, PageQueue, ProductFetch, ResponseArchive, Scraper},
+    supermarket::Supermarket,
+};
+
+use super::{
+    get_all_products, get_categories, get_pickup_stores, get_product_ids, get_stores,
+    parse_archived_products, save_pickup_stores, save_products, save_store, set_fulfillment_method,
+    set_location, Category, Product, Store, COUNTDOWN_BASE_URL, DEFAULT_USER_AGENT,
+};
+
+/// Retrieves prices from the Countdown API.
+#[allow(clippy::module_name_repetitions)]
+pub struct CountdownScraper {
+    client: Client,
+    base_url: String,
+    retry_config: RetryConfig,
+    rate_limiter: RateLimiter,
+}
+
+impl CountdownScraper {
+    /// Creates a new scraper, with an HTTP client configured to look like a
+    /// browser to the Countdown API.
+    ///
+    /// Requests which fail with a transient error are retried according to
+    /// `retry_config`, and products are requested no faster than `rate_limit`.
+    ///
+    /// # Errors
+    /// If unable to create the HTTP client.
+    pub fn new(
+        retry_config: RetryConfig,
+        rate_limit: RateLimitConfig,
+    ) -> Result<Self, ApplicationError> {
+        Self::with_base_url(COUNTDOWN_BASE_URL, retry_config, rate_limit)
+    }
+
+    /// Creates a new scraper which makes requests to a Countdown API hosted
+    /// at `base_url`, rather than [`COUNTDOWN_BASE_URL`].
+    ///
+    /// This allows the scraper to be run against a local stand-in of the API.
+    ///
+    /// # Errors
+    /// If unable to create the HTTP client.
+    pub fn with_base_url(
+        base_url: impl Into<String>,
+        retry_config: RetryConfig,
+        rate_limit: RateLimitConfig,
+    ) -> Result<Self, ApplicationError> {
+        let mut default_headers = reqwest::header::HeaderMap::new();
+        default_headers.insert(
+            "accept-language",
+            reqwest::header::HeaderValue::from_static("en-US,en;q=0.9"),
+        );
+        default_headers.insert(
+            "x-requested-with",
+            reqwest::header::HeaderValue::from_static("OnlineShopping.WebApp"),
+        );
+
+        let client = reqwest::Client::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .default_headers(default_headers)
+            .cookie_store(true)
+            .build()
+            .change_context(ApplicationError::HttpError)?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.into(),
+            retry_config,
+            rate_limiter: RateLimiter::new(rate_limit),
+        })
+    }
+
+    /// Discovers every Countdown store which offers pickup, saving them into
+    /// the database so they can be scraped.
+    ///
+    /// `should_insert` indicates if the discovered stores should be saved.
+    ///
+    /// # Errors
+    /// - If unable to retrieve the pickup stores
+    /// - If unable to save the stores
+    pub async fn discover_stores(
+        &self,
+        pool: &PgPool,
+        should_insert: bool,
+    ) -> Result<(), ApplicationError> {
+        let stores = get_pickup_stores(&self.client, &self.base_url).await?;
+        tracing::info!("Discovered {} stores", stores.len());
+        for store in &stores {
+            tracing::debug!(
+                "Store {} '{}' in {}: {}",
+                store.id,
+                store.name,
+                store.region,
+                store.address.as_deref().unwrap_or("unknown address")
+            );
+        }
+
+        if should_insert {
+            save_pickup_stores(pool, &stores).await?;
+        } else {
+            tracing::debug!("Skipped inserting stores into database");
+        }
+
+        Ok(())
+    }
+}
+
+impl Scraper for CountdownScraper {
+    type Store = Store;
+    type Category = Category;
+    type Product = Product;
+
+    const SUPERMARKET: Supermarket = Supermarket::Countdown;
+    // Countdown Mt Eden and Woolworths Hornby
+    const DEFAULT_STORES: &'static [&'static str] = &["1906076", "1352617"];
+
+    async fn list_stores(&self, pool: &PgPool) -> Result<Vec<Store>, ApplicationError> {
+        get_stores(pool).await
+    }
+
+    async fn select_store(&self, store: &Store) -> Result<(), ApplicationError> {
+        set_fulfillment_method(&self.client, &self.base_url, &self.retry_config)
+            .await
+            .change_context(ApplicationError::SetLocation)
+            .attach_printable("When setting fulfillment method to pickup")?;
+
+        set_location(&self.client, &self.base_url, store.id, &self.retry_config)
+            .await
+            .change_context(ApplicationError::SetLocation)
+    }
+
+    async fn list_categories(
+        &self,
+        _store: &Store,
+        archive: &ResponseArchive,
+    ) -> Result<Vec<Category>, ApplicationError> {
+        get_categories(&self.client, &self.base_url, &self.retry_config, archive)
+            .await
+            .change_context(ApplicationError::CategoryRetrieval)
+    }
+
+    async fn fetch_products(
+        &self,
+        _store: &Store,
+        categories: Vec<Category>,
+        queue: &PageQueue,
+        archive: &ResponseArchive,
+    ) -> Result<ProductFetch<Product>, ApplicationError> {
+        get_all_products(
+            &self.client,
+            &self.base_url,
+            categories,
+            &self.retry_config,
+            &self.rate_limiter,
+            queue,
+            archive,
+        )
+        .await
+        .change_context(ApplicationError::ProductRetrieval)
+    }
+
+    fn parse_archive(
+        &self,
+        responses: &[ArchivedResponse],
+    ) -> Result<ProductFetch<Product>, ApplicationError> {
+        parse_archived_products(responses).change_context(ApplicationError::ProductRetrieval)
+    }
+
+    async fn save_products(
+        &self,
+        pool: &PgPool,
+        products: &[Product],
+    ) -> Result<(), ApplicationError> {
+        save_products(pool, products).await
+    }
+
+    async fn save_store(&self, pool: &PgPool, store: &Store) -> Result<i32, ApplicationError> {
+        save_store(pool, store.id, store.name.clone())
+            .await
+            .change_context(ApplicationError::SaveStore)
+    }
+
+    async fn get_product_ids(
+        &self,
+        pool: &PgPool,
+    ) -> Result<HashMap<String, i32>, ApplicationError> {
+        get_product_ids(pool).await
+    }
+}
@@ -2,19 +2,31 @@ use error_stack::Result;
 use reqwest::Client;
 use serde_json::json;
 
+use crate::retry::{retry, RetryConfig};
+
 /// Sets the fulfillment method to be "pickup", so we can set precise store locations.
+///
+/// Retries the request according to `retry_config` on transient errors.
 #[tracing::instrument(
     name = "set fulfillment method",
     level = "debug",
     skip_all,
     fields(method = "pickup")
 )]
-pub async fn set_fulfillment_method(client: &Client, base_url: &str) -> Result<(), reqwest::Error> {
-    client
-        .put(&format!("{base_url}/fulfilment/my/methods/pickup"))
-        .json(&json!({}))
-        .send()
-        .await?;
+pub async fn set_fulfillment_method(
+    client: &Client,
+    base_url: &str,
+    retry_config: &RetryConfig,
+) -> Result<(), reqwest::Error> {
+    retry(retry_config, || async {
+        client
+            .put(format!("{base_url}/fulfilment/my/methods/pickup"))
+            .json(&json!({}))
+            .send()
+            .await?
+            .error_for_status()
+    })
+    .await?;
 
     Ok(())
 }
@@ -2,7 +2,11 @@ use error_stack::Result;
 use reqwest::Client;
 use serde_json::json;
 
+use crate::retry::{retry, RetryConfig};
+
 /// Sets the location of the countdown store to a specified ID.
+///
+/// Retries the request according to `retry_config` on transient errors.
 #[tracing::instrument(
 	name = "set store location",
 	level = "debug",
@@ -15,14 +19,19 @@ pub async fn set_location(
     client: &Client,
     base_url: &str,
     store_id: i32,
+    retry_config: &RetryConfig,
 ) -> Result<(), reqwest::Error> {
-    client
-        .put(&format!("{base_url}/fulfilment/my/pickup-addresses"))
-        .json(&json!({
-            "addressId": store_id
-        }))
-        .send()
-        .await?;
+    retry(retry_config, || async {
+        client
+            .put(format!("{base_url}/fulfilment/my/pickup-addresses"))
+            .json(&json!({
+                "addressId": store_id
+            }))
+            .send()
+            .await?
+            .error_for_status()
+    })
+    .await?;
 
     Ok(())
 }
@@ -0,0 +1,20 @@
+use crate::scraper::ScrapedStore;
+
+/// Represents a physical Countdown store.
+#[derive(Clone, Debug)]
+pub struct Store {
+    /// The address id of the store, used when setting the pickup location.
+    pub id: i32,
+    /// The name of the store.
+    pub name: String,
+}
+
+impl ScrapedStore for Store {
+    fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
@@ -0,0 +1,200 @@
+use std::{collections::HashSet, sync::Mutex};
+
+use chrono::{DateTime, Local};
+use error_stack::{Report, Result, ResultExt};
+use futures::future::join_all;
+use sqlx::PgPool;
+
+use crate::{
+    cli::{DaemonArgs, RateLimitArgs, ScrapeArgs},
+    config::{ApplicationConfig, ConfigFile, ScheduleTable, StoreSelection},
+    error::ApplicationError,
+    scrape_supermarket::scrape_supermarket,
+    scraper::Shutdown,
+    supermarket::Supermarket,
+};
+
+/// Scrapes supermarkets on the `[[schedule]]` tables of the configuration
+/// `file`, until SIGTERM or Ctrl-C is received.
+///
+/// The same stores of a supermarket are never scraped twice at once, so a
+/// scrape that is due while its stores are still being scraped is skipped. Once shut down, scrapes
+/// that are running stop retrieving products, but finish saving products
+/// they had finished retrieving, as described by [`Shutdown`].
+///
+/// # Errors
+/// - If no schedules are configured
+/// - If the configuration of a scheduled scrape is invalid
+pub async fn run_daemon(
+    connection: PgPool,
+    args: DaemonArgs,
+    file: &ConfigFile,
+) -> Result<(), ApplicationError> {
+    if file.schedules.is_empty() {
+        return Err(Report::new(ApplicationError::Config).attach_printable(
+            "suggestion: add a [[schedule]] table to the configuration file for each supermarket to scrape",
+        ));
+    }
+
+    let schedules = file
+        .schedules
+        .iter()
+        .map(|schedule| {
+            let args = ScrapeArgs {
+                supermarket: schedule.supermarket.clone(),
+                stores: schedule.stores.clone(),
+                no_insert: args.no_insert,
+                price_storage: args.price_storage,
+                retry: args.retry.clone(),
+                rate_limit: RateLimitArgs::default(),
+                cache: args.cache.clone(),
+                resume: None,
+                replay: None,
+                reparse: None,
+            };
+
+            ApplicationConfig::from_args(args, file)
+                .change_context(ApplicationError::Config)
+                .map(|config| (schedule, config))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (trigger, shutdown) = Shutdown::new();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        tracing::info!("Shutting down, once running scrapes finish saving");
+        trigger.request();
+    });
+
+    let running = RunningScrapes::default();
+    join_all(schedules.iter().map(|(schedule, config)| {
+        run_schedule(&connection, schedule, config, &running, &shutdown)
+    }))
+    .await;
+
+    tracing::info!("Shut down");
+    Ok(())
+}
+
+/// Scrapes the supermarket each time the `schedule` is due, until `shutdown`
+/// is requested.
+///
+/// `running` holds the scrapes of every schedule.
+async fn run_schedule(
+    connection: &PgPool,
+    schedule: &ScheduleTable,
+    config: &ApplicationConfig,
+    running: &RunningScrapes,
+    shutdown: &Shutdown,
+) {
+    let supermarket = &config.supermarket;
+    let mut last_due: Option<DateTime<Local>> = None;
+
+    loop {
+        // never schedule a scrape before one that was already due
+        let after = last_due.map_or_else(Local::now, |due| due.max(Local::now()));
+        let Some(due) = schedule.cron.after(&after).next() else {
+            tracing::warn!("The schedule of {supermarket} is never due again");
+            return;
+        };
+        last_due = Some(due);
+
+        tracing::info!("Scraping {supermarket} next at {due}");
+        let delay = (due - Local::now()).to_std().unwrap_or_default();
+        if shutdown
+            .run_until(tokio::time::sleep(delay))
+            .await
+            .is_none()
+        {
+            return;
+        }
+
+        if !running.start(supermarket, &config.stores) {
+            tracing::warn!(
+                "Skipping the scrape of {supermarket} due at {due}, as its stores are still being scraped"
+            );
+            continue;
+        }
+
+        let result = scrape_supermarket(connection.clone(), config, shutdown).await;
+        running.finish(supermarket, &config.stores);
+
+        match result {
+            Ok(()) => tracing::info!("Finished scraping {supermarket}"),
+            Err(report) => tracing::error!("Failed to scrape {supermarket}: {report:?}"),
+        }
+    }
+}
+
+/// The supermarkets being scraped by the schedules, and which of their stores.
+///
+/// Schedules of different stores of a supermarket may run at once, as each
+/// store is also locked while it is scraped.
+#[derive(Default)]
+struct RunningScrapes(Mutex<HashSet<(Supermarket, StoreSelection)>>);
+
+impl RunningScrapes {
+    /// Records that the `stores` of the `supermarket` are being scraped,
+    /// returning `false` if they already are.
+    fn start(&self, supermarket: &Supermarket, stores: &StoreSelection) -> bool {
+        self.lock().insert((supermarket.clone(), stores.clone()))
+    }
+
+    /// Records that the `stores` of the `supermarket` are no longer being
+    /// scraped.
+    fn finish(&self, supermarket: &Supermarket, stores: &StoreSelection) {
+        self.lock().remove(&(supermarket.clone(), stores.clone()));
+    }
+
+    /// Locks the scrapes running.
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashSet<(Supermarket, StoreSelection)>> {
+        // the set is never left half-updated, so a poisoned lock is still usable
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Waits until SIGTERM or Ctrl-C is received.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(error) => {
+                tracing::warn!("Unable to listen for SIGTERM: {error}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        () = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_skips_scrapes_of_the_same_stores() {
+        let running = RunningScrapes::default();
+        let royal_oak = StoreSelection::Only(vec!["PAK'nSAVE Royal Oak".to_string()]);
+        let riccarton = StoreSelection::Only(vec!["PAK'nSAVE Riccarton".to_string()]);
+
+        assert!(running.start(&Supermarket::PakNSave, &royal_oak));
+        assert!(running.start(&Supermarket::PakNSave, &riccarton));
+        assert!(running.start(&Supermarket::NewWorld, &royal_oak));
+        assert!(!running.start(&Supermarket::PakNSave, &royal_oak));
+
+        running.finish(&Supermarket::PakNSave, &royal_oak);
+        assert!(running.start(&Supermarket::PakNSave, &royal_oak));
+        assert!(!running.start(&Supermarket::PakNSave, &riccarton));
+    }
+}
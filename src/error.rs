@@ -13,16 +13,30 @@ pub enum ApplicationError {
     DatabaseInitializeError,
     /// Errors associated with using [`reqwest`]
     HttpError,
+    /// Errors associated with retrieving the stores of a supermarket.
+    StoreRetrieval,
     /// Errors associated with retrieving the categories of products.
     CategoryRetrieval,
     /// Errors associated with retrieving the products.
     ProductRetrieval,
-    /// Failed to write response cache to disk
+    /// Failed to read or write the response cache on disk
     CacheError,
+    /// Failed to read or write the archive of raw responses on disk
+    ArchiveError,
     /// Failed to insert new products into the database
     NewProductsInsertionError,
+    /// Failed to insert the categories of products into the database
+    CategoryInsertionError,
     /// Failed to insert prices of products into the database
     PriceDataInsertionError,
+    /// Failed to record a scrape run in the database
+    ScrapeRunError,
+    /// Failed to persist the queue of pages a scrape run needs to retrieve
+    WorkQueueError,
+    /// Failed to lock a store, so that only one run scrapes it at a time
+    StoreLockError,
+    /// Failed to query or export the recorded products and prices
+    QueryError,
     /// Failed to set the location to a specified store
     SetLocation,
     /// Failed to save the store to the database
@@ -40,19 +54,38 @@ impl fmt::Display for ApplicationError {
             ApplicationError::HttpError => {
                 write!(f, "An error occurred while performing an HTTP request")
             }
+            ApplicationError::StoreRetrieval => write!(f, "Failed to retrieve stores"),
             ApplicationError::CategoryRetrieval => {
                 write!(f, "Failed to retrieve categories of products")
             }
             ApplicationError::ProductRetrieval => {
                 write!(f, "Failed to retrieve all products")
             }
-            ApplicationError::CacheError => write!(f, "Failed to write cache"),
+            ApplicationError::CacheError => write!(f, "Failed to read or write cache"),
+            ApplicationError::ArchiveError => {
+                write!(f, "Failed to read or write the response archive")
+            }
             ApplicationError::NewProductsInsertionError => {
                 write!(f, "Failed to insert new products into database")
             }
+            ApplicationError::CategoryInsertionError => {
+                write!(f, "Failed to insert product categories into database")
+            }
             ApplicationError::PriceDataInsertionError => {
                 write!(f, "Failed to write price feed into database")
             }
+            ApplicationError::ScrapeRunError => {
+                write!(f, "Failed to record the scrape run in the database")
+            }
+            ApplicationError::WorkQueueError => {
+                write!(f, "Failed to persist the queue of pages to retrieve")
+            }
+            ApplicationError::StoreLockError => {
+                write!(f, "Failed to lock the store for scraping")
+            }
+            ApplicationError::QueryError => {
+                write!(f, "Failed to query the recorded products and prices")
+            }
             ApplicationError::SetLocation => {
                 write!(f, "Failed to set location of store")
             }
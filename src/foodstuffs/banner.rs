@@ -0,0 +1,42 @@
+use crate::supermarket::Supermarket;
+
+/// A Foodstuffs brand, such as New World or Pak'nSave.
+///
+/// Every banner exposes the same API under its own domain, and shares product
+/// identifiers with the other banners, so they only differ in where their API
+/// is and which stores they have.
+pub trait Banner: Send + Sync {
+    /// The supermarket of the banner.
+    const SUPERMARKET: Supermarket;
+    /// The names of the stores to retrieve prices from when no stores are
+    /// configured.
+    const DEFAULT_STORES: &'static [&'static str];
+    /// The URL of the API listing stores and categories.
+    const BASE_URL: &'static str;
+    /// The URL of the API listing products.
+    const BRANDS_API_URL: &'static str;
+}
+
+/// The New World banner.
+pub struct NewWorld;
+
+/// The Pak'nSave banner.
+pub struct PakNSave;
+
+impl Banner for NewWorld {
+    const SUPERMARKET: Supermarket = Supermarket::NewWorld;
+    // As with Countdown, North Island and South Island have differing prices,
+    // so we pick a large New World in each island.
+    const DEFAULT_STORES: &'static [&'static str] = &["New World Victoria Park", "New World Ilam"];
+    const BASE_URL: &'static str = "https://www.newworld.co.nz/CommonApi";
+    const BRANDS_API_URL: &'static str = "https://www.newworld.co.nz/BrandsApi";
+}
+
+impl Banner for PakNSave {
+    const SUPERMARKET: Supermarket = Supermarket::PakNSave;
+    // As with Countdown, North Island and South Island have differing prices,
+    // so we pick a large Pak'nSave in each island.
+    const DEFAULT_STORES: &'static [&'static str] = &["PAK'nSAVE Royal Oak", "PAK'nSAVE Riccarton"];
+    const BASE_URL: &'static str = "https://www.paknsave.co.nz/CommonApi";
+    const BRANDS_API_URL: &'static str = "https://www.paknsave.co.nz/BrandsApi";
+}
@@ -0,0 +1,55 @@
+use error_stack::{Result, ResultExt};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::error::ApplicationError;
+
+use super::Category;
+
+/// A single item in the mega menu.
+#[derive(Deserialize)]
+struct NavigationItem {
+    /// The nested categories beneath this item.
+    #[serde(rename = "Children", default)]
+    children: Vec<Category>,
+}
+
+/// Describes the response from the `Navigation/MegaMenu` endpoint.
+#[derive(Deserialize)]
+struct MegaMenuResponse {
+    #[serde(rename = "NavigationList")]
+    navigation_list: Vec<NavigationItem>,
+}
+
+/// Retrieves all the top-level categories available at the given store.
+///
+/// # Errors
+/// - If unable to perform the HTTP request
+/// - If unable to decode the JSON response
+#[tracing::instrument(name = "get categories", level = "debug", skip_all, fields(%store_id))]
+pub async fn get_categories(
+    client: &Client,
+    base_url: &str,
+    store_id: &str,
+) -> Result<Vec<Category>, ApplicationError> {
+    let res: MegaMenuResponse = client
+        .get(format!("{base_url}/Navigation/MegaMenu"))
+        .query(&[("storeId", store_id), ("v", "")])
+        .send()
+        .await
+        .change_context(ApplicationError::HttpError)?
+        .json()
+        .await
+        .change_context(ApplicationError::CategoryRetrieval)?;
+
+    // only the departments (directly beneath each navigation item) are
+    // searched, as every nested category is contained within a department
+    let categories = res
+        .navigation_list
+        .into_iter()
+        .flat_map(|item| item.children)
+        .filter(|category| category.url.starts_with("/shop/category/"))
+        .collect();
+
+    Ok(categories)
+}
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+
+use crate::error::ApplicationError;
+
+/// Retrieves every Foodstuffs (New World and Pak'nSave) product stored in the
+/// database, as a map from the product's Foodstuffs identifier to its id in
+/// the `products` table.
+///
+/// # Errors
+/// If unable to retrieve the products from the database
+pub async fn get_product_ids(pool: &PgPool) -> Result<HashMap<String, i32>, ApplicationError> {
+    let product_ids = sqlx::query!(
+        r"SELECT products.id, foodstuffs_products.product_id FROM products
+			INNER JOIN foodstuffs_products
+			ON products.foodstuffs_id = foodstuffs_products.id
+			WHERE foodstuffs_id IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .change_context(ApplicationError::ProductRetrieval)?
+    .into_iter()
+    .map(|product| (product.product_id, product.id))
+    .collect();
+
+    Ok(product_ids)
+}
@@ -0,0 +1,325 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use error_stack::{Result, ResultExt};
+use futures::{stream, StreamExt, TryStreamExt};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::Span;
+
+use crate::{
+    error::ApplicationError,
+    rate_limiter::RateLimiter,
+    scraper::{DriftReport, FailedPage, PageQueue, Price, ProductFetch, QueuedPage},
+};
+
+use super::{Category, Product};
+
+/// Describes the response returned by the `Algoliasearch/GetGroceriesItems` endpoint.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GroceriesItemsResponse {
+    /// All the items on the current page.
+    hits: Vec<ProductResponse>,
+    /// The total amount of pages for the query.
+    nb_pages: i64,
+}
+
+/// A single product that can be purchased.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductResponse {
+    /// The Foodstuffs identifier of the product.
+    product_id: String,
+    /// The display name of the product.
+    name: String,
+    /// The brand of the product.
+    brand: Option<String>,
+    /// The price of the product.
+    price: ProductPrice,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductPrice {
+    /// The current price of the product, in dollars.
+    sale_price: f32,
+    /// The normal price of the product, in dollars.
+    ///
+    /// Only present if the product is on special.
+    original_price: Option<f32>,
+}
+
+/// The size amount of items to query for each page.
+const PAGE_SIZE: i64 = 50;
+
+/// The response for a request to browse a category.
+#[derive(Debug)]
+pub struct GetProductResponse {
+    /// The current page's products
+    pub products: HashSet<Product>,
+    /// The next page number, or None if we have reached the end.
+    pub next_page: Option<i64>,
+}
+
+/// Retrieves a single page of products in a category at a store.
+///
+/// Pages are zero-indexed.
+///
+/// # Errors
+/// - If unable to perform the HTTP request
+/// - If unable to decode the JSON response
+#[tracing::instrument(
+	name = "get products",
+	level = "trace",
+	skip_all,
+	fields(
+		%page_number,
+		%category,
+		product_count_retrieved = tracing::field::Empty
+	)
+)]
+pub async fn get_products(
+    client: &Client,
+    brands_api_url: &str,
+    store_id: &str,
+    page_number: i64,
+    category: &Category,
+    rate_limiter: &RateLimiter,
+) -> Result<GetProductResponse, reqwest::Error> {
+    // our Category contains url information
+    // but we only want the last part of the url
+    let category_url_part = category.url.split('/').next_back().unwrap_or_default();
+
+    let res: GroceriesItemsResponse = rate_limiter
+        .send(
+            client
+                .get(format!("{brands_api_url}/Algoliasearch/GetGroceriesItems"))
+                .query(&[
+                    ("storeId", store_id.to_string()),
+                    ("category", category_url_part.to_string()),
+                    ("page", page_number.to_string()),
+                    ("hitsPerPage", PAGE_SIZE.to_string()),
+                ]),
+        )
+        .await?
+        .json()
+        .await?;
+
+    let products = res
+        .hits
+        .into_iter()
+        .map(|item| {
+            let sale = item.price.sale_price;
+            let original = item.price.original_price.unwrap_or(sale);
+
+            Product {
+                name: item.name,
+                product_id: item.product_id,
+                brand: item.brand.filter(|brand| !brand.is_empty()),
+                categories: [category.name.clone()].into(),
+                price: Price::from_dollars(original, sale, original - sale),
+            }
+        })
+        .collect::<HashSet<Product>>();
+
+    let is_end = page_number + 1 >= res.nb_pages || products.is_empty();
+
+    Span::current().record("product_count_retrieved", products.len());
+
+    Ok(GetProductResponse {
+        products,
+        next_page: (!is_end).then_some(page_number + 1),
+    })
+}
+
+/// Retrieves every page of products for a given [`Category`], starting from
+/// `first_page`.
+///
+/// Each page is completed in the `queue` once retrieved, after queueing the
+/// next page. As the amount of pages is only known from a successful
+/// response, the remaining pages of the category are skipped if a page fails.
+///
+/// # Errors
+/// If unable to track the pages in the queue.
+async fn get_category_products(
+    client: &Client,
+    brands_api_url: &str,
+    store_id: &str,
+    category: &Category,
+    first_page: i64,
+    rate_limiter: &RateLimiter,
+    queue: &PageQueue,
+) -> Result<(Vec<Product>, Option<FailedPage>), ApplicationError> {
+    let mut products = Vec::new();
+    let mut page = Some(first_page);
+
+    while let Some(page_number) = page {
+        let res = get_products(
+            client,
+            brands_api_url,
+            store_id,
+            page_number,
+            category,
+            rate_limiter,
+        )
+        .await;
+
+        match res {
+            Ok(res) => {
+                page = res.next_page;
+                if let Some(next_page) = page {
+                    queue
+                        .enqueue(&[QueuedPage {
+                            category: category.name.clone(),
+                            page: next_page,
+                        }])
+                        .await?;
+                }
+
+                let page_products = res.products.into_iter().collect::<Vec<_>>();
+                queue
+                    .complete(
+                        &QueuedPage {
+                            category: category.name.clone(),
+                            page: page_number,
+                        },
+                        &page_products,
+                    )
+                    .await?;
+                products.extend(page_products);
+            }
+            Err(error) => {
+                tracing::warn!("Failed to retrieve page {page_number} of '{category}': {error:?}");
+                return Ok((
+                    products,
+                    Some(FailedPage {
+                        category: category.name.clone(),
+                        page: page_number,
+                        error: format!("{error:?}"),
+                    }),
+                ));
+            }
+        }
+    }
+
+    Ok((products, None))
+}
+
+/// Retrieves all the products for the given categories at a store.
+///
+/// Waits for `rate_limiter` before each request, to prevent rate-limiting.
+///
+/// Runs [`RateLimiter::concurrent_requests`] categories at once. Pages which
+/// fail are skipped and reported in the returned [`ProductFetch`].
+///
+/// Pages are tracked in the `queue`. If the queue has progress from a
+/// previous attempt, each category resumes from its pending page.
+///
+/// # Errors
+/// - If unable to track the pages in the queue
+/// - If every category failed to be retrieved
+#[tracing::instrument(name = "get_all_products", skip_all, fields(
+	num_categories = %categories.len()
+))]
+pub async fn get_all_products(
+    client: &Client,
+    brands_api_url: &str,
+    store_id: &str,
+    categories: Vec<Category>,
+    rate_limiter: &RateLimiter,
+    queue: &PageQueue,
+) -> Result<ProductFetch<Product>, ApplicationError> {
+    let (pending, resumed_products) = if let Some(progress) = queue.progress::<Product>().await? {
+        tracing::info!(
+            "Resuming with {} pending pages, and {} products already retrieved",
+            progress.pending.len(),
+            progress.products.len()
+        );
+
+        let categories = categories
+            .into_iter()
+            .map(|category| (category.name.clone(), category))
+            .collect::<HashMap<_, _>>();
+        let pending = progress
+            .pending
+            .into_iter()
+            .filter_map(|pending| {
+                let category = categories.get(&pending.category).cloned();
+                if category.is_none() {
+                    tracing::warn!("Skipping '{}' as it no longer exists", pending.category);
+                }
+                category.map(|category| (category, pending.page))
+            })
+            .collect::<Vec<_>>();
+
+        (pending, progress.products)
+    } else {
+        let pending = categories
+            .into_iter()
+            .map(|category| (category, 0))
+            .collect::<Vec<_>>();
+        queue
+            .enqueue(
+                &pending
+                    .iter()
+                    .map(|(category, page)| QueuedPage {
+                        category: category.name.clone(),
+                        page: *page,
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .await?;
+
+        (pending, Vec::new())
+    };
+
+    let (product_results, failed_pages): (Vec<_>, Vec<_>) = stream::iter(pending)
+        .map(|(category, first_page)| async move {
+            get_category_products(
+                client,
+                brands_api_url,
+                store_id,
+                &category,
+                first_page,
+                rate_limiter,
+                queue,
+            )
+            .await
+        })
+        .buffer_unordered(rate_limiter.concurrent_requests())
+        .try_collect::<Vec<_>>()
+        .await?
+        .into_iter()
+        .unzip();
+    let failed_pages = failed_pages.into_iter().flatten().collect::<Vec<_>>();
+
+    // products may be found in multiple categories, so we merge them
+    let mut products: HashMap<String, Product> = HashMap::new();
+    for product in product_results
+        .into_iter()
+        .flatten()
+        .chain(resumed_products)
+    {
+        match products.entry(product.product_id.clone()) {
+            Entry::Occupied(mut entry) => entry.get_mut().merge(product),
+            Entry::Vacant(entry) => {
+                entry.insert(product);
+            }
+        }
+    }
+
+    if products.is_empty() && !failed_pages.is_empty() {
+        return Err(ApplicationError::ProductRetrieval).attach_printable(format!(
+            "All {} categories failed, the first with {}",
+            failed_pages.len(),
+            failed_pages[0]
+        ));
+    }
+
+    Ok(ProductFetch {
+        products: products.into_values().collect(),
+        failed_pages,
+        // Foodstuffs responses are not checked for drift
+        drift: DriftReport::default(),
+    })
+}
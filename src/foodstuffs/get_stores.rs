@@ -0,0 +1,34 @@
+use error_stack::{Result, ResultExt};
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::error::ApplicationError;
+
+use super::Store;
+
+/// Describes the response from the `Store/GetStoreList` endpoint.
+#[derive(Deserialize)]
+struct GetStoreList {
+    stores: Vec<Store>,
+}
+
+/// Retrieves all the stores of the Foodstuffs banner at `base_url`.
+///
+/// Uses the `Store/GetStoreList` endpoint.
+///
+/// # Errors
+/// - If unable to perform the HTTP request
+/// - If unable to decode the JSON response
+#[tracing::instrument(name = "get stores", level = "debug", skip_all)]
+pub async fn get_stores(client: &Client, base_url: &str) -> Result<Vec<Store>, ApplicationError> {
+    let res: GetStoreList = client
+        .get(format!("{base_url}/Store/GetStoreList"))
+        .send()
+        .await
+        .change_context(ApplicationError::HttpError)?
+        .json()
+        .await
+        .change_context(ApplicationError::StoreRetrieval)?;
+
+    Ok(res.stores)
+}
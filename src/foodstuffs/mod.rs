@@ -0,0 +1,27 @@
+//! New World and Pak'nSave are Foodstuffs banners, sharing the same API and
+//! product identifiers, so a single [`FoodstuffsScraper`] retrieves the prices
+//! of either [`Banner`].
+
+mod banner;
+mod category;
+mod get_categories;
+mod get_product_ids;
+mod get_products;
+mod get_stores;
+mod product;
+mod save_products;
+mod save_store;
+mod scraper;
+mod store;
+
+pub use banner::{Banner, NewWorld, PakNSave};
+pub use category::Category;
+pub use get_categories::get_categories;
+pub use get_product_ids::get_product_ids;
+pub use get_products::{get_all_products, get_products};
+pub use get_stores::get_stores;
+pub use product::Product;
+pub use save_products::save_products;
+pub use save_store::save_store;
+pub use scraper::{FoodstuffsScraper, NewWorldScraper, PakNSaveScraper};
+pub use store::Store;
@@ -0,0 +1,63 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::scraper::{Price, ScrapedProduct};
+
+/// Represents a product that can be purchased from a Foodstuffs banner.
+///
+/// We define two products to be equal if their `product_id` values are equal.
+/// Similarly, we define the hash of a product to be solely from its
+/// `product_id`, and not from any other field.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Product {
+    /// The name of the product.
+    pub name: String,
+    /// The Foodstuffs identifier of the product, e.g., `5039965-EA-000`.
+    pub product_id: String,
+    /// The brand of the product, e.g., `Anchor`.
+    pub brand: Option<String>,
+    /// The names of the categories the product was found in.
+    pub categories: BTreeSet<String>,
+    /// The current price of the product.
+    pub price: Price,
+}
+
+impl Eq for Product {}
+impl PartialEq for Product {
+    fn eq(&self, other: &Self) -> bool {
+        self.product_id.eq(&other.product_id)
+    }
+}
+
+impl Product {
+    /// Merges the categories of another instance of the same product into
+    /// this product.
+    pub fn merge(&mut self, other: Product) {
+        self.categories.extend(other.categories);
+    }
+}
+
+impl std::hash::Hash for Product {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.product_id.hash(state);
+    }
+}
+
+impl ScrapedProduct for Product {
+    fn key(&self) -> &str {
+        &self.product_id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn categories(&self) -> &BTreeSet<String> {
+        &self.categories
+    }
+
+    fn price(&self) -> Price {
+        self.price
+    }
+}
@@ -0,0 +1,75 @@
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::error::ApplicationError;
+
+use super::Product;
+
+/// Saves new Foodstuffs (New World and Pak'nSave) products into the database.
+///
+/// If a product already exists in the database (by product id), only its brand
+/// is updated.
+#[tracing::instrument(name = "save products", level = "debug", skip_all, fields(
+	product_count = %products.len()
+))]
+pub async fn save_products(pool: &PgPool, products: &[Product]) -> Result<(), ApplicationError> {
+    let mut names = Vec::with_capacity(products.len());
+    let mut product_ids = Vec::with_capacity(products.len());
+    let mut brands = Vec::with_capacity(products.len());
+
+    for product in products {
+        names.push(product.name.clone());
+        product_ids.push(product.product_id.clone());
+        brands.push(product.brand.clone());
+    }
+
+    // update the brands of existing products
+    sqlx::query!(
+        r#"
+		UPDATE foodstuffs_products SET
+			brand = data.brand
+		FROM UNNEST ($1::text[], $2::text[]) AS data(product_id, brand)
+		WHERE foodstuffs_products.product_id = data.product_id
+			AND foodstuffs_products.brand IS DISTINCT FROM data.brand
+		"#,
+        &product_ids[..],
+        &brands[..] as &[Option<String>]
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::NewProductsInsertionError)?;
+
+    // insert into `foodstuffs_products` table
+    let new_foodstuffs_products = sqlx::query!(
+        r#"
+		INSERT INTO foodstuffs_products (name, product_id, brand)
+			SELECT * FROM UNNEST ($1::text[], $2::text[], $3::text[])
+			ON CONFLICT (product_id) DO NOTHING
+			RETURNING id
+		"#,
+        &names[..],
+        &product_ids[..],
+        &brands[..] as &[Option<String>]
+    )
+    .fetch_all(pool)
+    .await
+    .change_context(ApplicationError::NewProductsInsertionError)?;
+    debug!("{} new products", new_foodstuffs_products.len());
+
+    // insert into `products` table
+    sqlx::query!(
+        r"INSERT INTO products (
+			foodstuffs_id
+		) SELECT * FROM UNNEST($1::integer[])",
+        &new_foodstuffs_products
+            .iter()
+            .map(|product| product.id)
+            .collect::<Vec<_>>()
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::NewProductsInsertionError)?;
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use error_stack::Result;
+use sqlx::PgPool;
+
+use crate::supermarket::Supermarket;
+
+/// Saves a store of a Foodstuffs banner into the database.
+///
+/// If the store already exists, returns the store id.
+///
+/// # Panics
+/// If the `supermarket` is not a Foodstuffs banner.
+#[tracing::instrument(
+	name = "save_store",
+	level = "debug",
+	skip_all,
+	fields(
+		%supermarket,
+		%id,
+		%name
+	)
+)]
+pub async fn save_store(
+    pool: &PgPool,
+    supermarket: &Supermarket,
+    id: &str,
+    name: &str,
+) -> Result<i32, sqlx::Error> {
+    // each banner has its own table of stores, referenced by its own column
+    // of `stores`
+    let (inserted, new_world_store_id, paknsave_store_id) = match supermarket {
+        Supermarket::NewWorld => (
+            sqlx::query!(
+                r#"INSERT INTO new_world_stores (
+			id, name ) VALUES (
+				$1, $2
+			) ON CONFLICT DO NOTHING
+			RETURNING id"#,
+                id,
+                name,
+            )
+            .fetch_optional(pool)
+            .await?
+            .is_some(),
+            Some(id),
+            None,
+        ),
+        Supermarket::PakNSave => (
+            sqlx::query!(
+                r#"INSERT INTO paknsave_stores (
+			id, name ) VALUES (
+				$1, $2
+			) ON CONFLICT DO NOTHING
+			RETURNING id"#,
+                id,
+                name,
+            )
+            .fetch_optional(pool)
+            .await?
+            .is_some(),
+            None,
+            Some(id),
+        ),
+        Supermarket::Countdown => unreachable!("Countdown is not a Foodstuffs banner"),
+    };
+    let supermarket = supermarket.to_string();
+
+    if inserted {
+        // insert and return new store id
+        let id = sqlx::query!(
+            "INSERT INTO stores (
+supermarket, new_world_store_id, paknsave_store_id
+) VALUES (
+$1::text::supermarket, $2, $3
+) RETURNING id",
+            supermarket,
+            new_world_store_id,
+            paknsave_store_id
+        )
+        .fetch_one(pool)
+        .await?;
+        return Ok(id.id);
+    }
+
+    // return the existing store id
+    let id = sqlx::query!(
+        r#"SELECT id FROM stores
+WHERE supermarket = $1::text::supermarket
+	AND (new_world_store_id = $2 OR paknsave_store_id = $3)"#,
+        supermarket,
+        new_world_store_id,
+        paknsave_store_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(id.id)
+}
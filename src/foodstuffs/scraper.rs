@@ -0,0 +1,120 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use error_stack::{Result, ResultExt};
+use reqwest::Client;
+use sqlx::PgPool;
+
+use crate::{
+    error::ApplicationError,
+    rate_limiter::{RateLimitConfig, RateLimiter},
+    scraper::{PageQueue, ProductFetch, ResponseArchive, Scraper},
+    supermarket::Supermarket,
+};
+
+use super::{
+    get_all_products, get_categories, get_product_ids, get_stores, save_products, save_store,
+    Banner, Category, NewWorld, PakNSave, Product, Store,
+};
+
+/// Retrieves prices from the Foodstuffs API of the banner `B`.
+#[allow(clippy::module_name_repetitions)]
+pub struct FoodstuffsScraper<B: Banner> {
+    client: Client,
+    rate_limiter: RateLimiter,
+    banner: PhantomData<B>,
+}
+
+/// Retrieves prices from the New World API.
+#[allow(clippy::module_name_repetitions)]
+pub type NewWorldScraper = FoodstuffsScraper<NewWorld>;
+
+/// Retrieves prices from the Pak'nSave API.
+#[allow(clippy::module_name_repetitions)]
+pub type PakNSaveScraper = FoodstuffsScraper<PakNSave>;
+
+impl<B: Banner> FoodstuffsScraper<B> {
+    /// Creates a new scraper, which requests products no faster than
+    /// `rate_limit`.
+    ///
+    /// # Errors
+    /// If unable to create the HTTP client.
+    pub fn new(rate_limit: RateLimitConfig) -> Result<Self, ApplicationError> {
+        let client = Client::builder()
+            .cookie_store(true)
+            .build()
+            .change_context(ApplicationError::HttpError)?;
+
+        Ok(Self {
+            client,
+            rate_limiter: RateLimiter::new(rate_limit),
+            banner: PhantomData,
+        })
+    }
+}
+
+impl<B: Banner> Scraper for FoodstuffsScraper<B> {
+    type Store = Store;
+    type Category = Category;
+    type Product = Product;
+
+    const SUPERMARKET: Supermarket = B::SUPERMARKET;
+    const DEFAULT_STORES: &'static [&'static str] = B::DEFAULT_STORES;
+
+    async fn list_stores(&self, _pool: &PgPool) -> Result<Vec<Store>, ApplicationError> {
+        get_stores(&self.client, B::BASE_URL).await
+    }
+
+    async fn select_store(&self, _store: &Store) -> Result<(), ApplicationError> {
+        // every request is made with an explicit `storeId`, so there is no
+        // session state to update
+        Ok(())
+    }
+
+    async fn list_categories(
+        &self,
+        store: &Store,
+        _archive: &ResponseArchive,
+    ) -> Result<Vec<Category>, ApplicationError> {
+        get_categories(&self.client, B::BASE_URL, &store.id).await
+    }
+
+    async fn fetch_products(
+        &self,
+        store: &Store,
+        categories: Vec<Category>,
+        queue: &PageQueue,
+        _archive: &ResponseArchive,
+    ) -> Result<ProductFetch<Product>, ApplicationError> {
+        get_all_products(
+            &self.client,
+            B::BRANDS_API_URL,
+            &store.id,
+            categories,
+            &self.rate_limiter,
+            queue,
+        )
+        .await
+    }
+
+    async fn save_products(
+        &self,
+        pool: &PgPool,
+        products: &[Product],
+    ) -> Result<(), ApplicationError> {
+        // the banners share product identifiers
+        save_products(pool, products).await
+    }
+
+    async fn save_store(&self, pool: &PgPool, store: &Store) -> Result<i32, ApplicationError> {
+        save_store(pool, &B::SUPERMARKET, &store.id, &store.name)
+            .await
+            .change_context(ApplicationError::SaveStore)
+    }
+
+    async fn get_product_ids(
+        &self,
+        pool: &PgPool,
+    ) -> Result<HashMap<String, i32>, ApplicationError> {
+        get_product_ids(pool).await
+    }
+}
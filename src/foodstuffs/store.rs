@@ -0,0 +1,22 @@
+use serde::Deserialize;
+
+use crate::scraper::ScrapedStore;
+
+/// Represents a physical store of a Foodstuffs banner.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Store {
+    /// A version 4 uuid of the store to identify it.
+    pub id: String,
+    /// The name of the store.
+    pub name: String,
+}
+
+impl ScrapedStore for Store {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
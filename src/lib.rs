@@ -1,15 +1,17 @@
-use std::time::Duration;
-
+pub mod cli;
 pub mod config;
 pub mod countdown;
+pub mod daemon;
 pub mod error;
+pub mod foodstuffs;
 pub mod initialize_database;
-pub mod new_world;
+pub mod rate_limiter;
+pub mod report;
+pub mod retry;
+pub mod scrape_supermarket;
+pub mod scraper;
 pub mod supermarket;
 pub mod telemetry;
 
-pub const CACHE_PATH: &str = "cache.json";
-/// The amount of milliseconds to wait between performing iterations on the pages.
-pub const PAGE_ITERATION_INTERVAL: Duration = Duration::from_millis(500);
-/// The amount of requests to perform in parallel.
-pub const CONCURRENT_REQUESTS: i64 = 2;
+/// The git version the application was built from.
+pub const GIT_VERSION: &str = env!("GIT_VERSION");
@@ -1,32 +1,58 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+};
+
+use clap::{CommandFactory, Parser};
 use dotenvy::dotenv;
-use error_stack::{Result, ResultExt};
-use sqlx::postgres::PgPoolOptions;
+use error_stack::{Report, Result, ResultExt};
+use sqlx::PgPool;
 
 use supermarket_tracker::{
-    config::Config,
-    countdown,
+    cli::{Cli, Command, ExportArgs, PricesArgs, ProductsArgs, StoresArgs},
+    config::{read_rate_limit, read_retry_config, ApplicationConfig, ConfigFile, DatabaseConfig},
+    countdown::CountdownScraper,
+    daemon::run_daemon,
     error::ApplicationError,
+    foodstuffs::{NewWorldScraper, PakNSaveScraper},
     initialize_database::initialize_database,
-    new_world,
+    report,
+    scrape_supermarket::scrape_supermarket,
+    scraper::{ScrapedStore, Scraper, Shutdown},
     supermarket::Supermarket,
     telemetry::{get_tracing_subscriber, init_subscriber},
 };
 
 #[tokio::main]
 async fn main() -> Result<(), ApplicationError> {
-    let subscriber = get_tracing_subscriber(std::io::stdout);
-    init_subscriber(subscriber);
-
     // ignore any error attempting to load .env file
     dotenv().ok();
 
-    let config = Config::read_from_env().change_context(ApplicationError::Config)?;
+    let cli = Cli::parse();
+
+    if let Command::Completions { shell } = cli.command {
+        clap_complete::generate(
+            shell,
+            &mut Cli::command(),
+            "supermarket-tracker",
+            &mut io::stdout(),
+        );
+        return Ok(());
+    }
+
+    let file = ConfigFile::load(cli.config.as_deref()).change_context(ApplicationError::Config)?;
+
+    // logs are written to stderr, so the output of commands can be piped
+    let subscriber = get_tracing_subscriber(std::io::stderr, &file.output.log);
+    init_subscriber(subscriber);
+
+    let database = DatabaseConfig::read(&file.database).change_context(ApplicationError::Config)?;
 
     // connect to database
     tracing::debug!("Connecting to database");
-    let connection = PgPoolOptions::new()
-        .max_connections(5)
-        .connect_with(config.database.connection_string())
+    let connection = database
+        .pool_options()
+        .connect_with(database.connection_string())
         .await
         .change_context(ApplicationError::DatabaseConnectError)?;
     tracing::debug!("Connected to database");
@@ -35,8 +61,143 @@ async fn main() -> Result<(), ApplicationError> {
         .await
         .change_context(ApplicationError::DatabaseInitializeError)?;
 
-    match config.application.supermarket {
-        Supermarket::Countdown => countdown::run(connection, config.database.should_insert).await,
-        Supermarket::NewWorld => new_world::run().await,
+    match cli.command {
+        Command::Scrape(args) => {
+            let config = ApplicationConfig::from_args(args, &file)
+                .change_context(ApplicationError::Config)?;
+            scrape_supermarket(connection, &config, &Shutdown::never()).await
+        }
+        Command::Stores(args) => stores(connection, args, &file).await,
+        Command::Products(args) => products(connection, args).await,
+        Command::Prices(args) => prices(connection, args).await,
+        Command::Export(args) => export(connection, args).await,
+        Command::Daemon(args) => run_daemon(connection, args, &file).await,
+        Command::Migrate => {
+            tracing::info!("The database is up to date");
+            Ok(())
+        }
+        Command::Completions { .. } => unreachable!("completions are generated without a database"),
+    }
+}
+
+/// Lists the known stores of the supermarket, or discovers them.
+async fn stores(
+    connection: PgPool,
+    args: StoresArgs,
+    file: &ConfigFile,
+) -> Result<(), ApplicationError> {
+    let retry = read_retry_config(&args.retry, &file.http);
+    let rate_limit = read_rate_limit(
+        &args.rate_limit,
+        &args.supermarket,
+        file.supermarket(&args.supermarket),
+    )
+    .change_context(ApplicationError::Config)?;
+
+    match (args.supermarket, args.discover) {
+        (Supermarket::Countdown, true) => {
+            CountdownScraper::new(retry, rate_limit)?
+                .discover_stores(&connection, !args.no_insert)
+                .await
+        }
+        (Supermarket::NewWorld | Supermarket::PakNSave, true) => Err(Report::new(
+            ApplicationError::Config,
+        )
+        .attach_printable(
+            "Store discovery is only required for Countdown, as Foodstuffs stores are always retrieved from the API",
+        )),
+        (Supermarket::Countdown, false) => {
+            print_stores(CountdownScraper::new(retry, rate_limit)?, &connection).await
+        }
+        (Supermarket::NewWorld, false) => {
+            print_stores(NewWorldScraper::new(rate_limit)?, &connection).await
+        }
+        (Supermarket::PakNSave, false) => {
+            print_stores(PakNSaveScraper::new(rate_limit)?, &connection).await
+        }
+    }
+}
+
+/// Prints the id and name of every known store of the supermarket.
+async fn print_stores<S: Scraper>(scraper: S, connection: &PgPool) -> Result<(), ApplicationError> {
+    for store in scraper.list_stores(connection).await? {
+        println!("{}\t{}", store.id(), store.name());
+    }
+
+    Ok(())
+}
+
+/// Prints the products tracked for the supermarket.
+async fn products(connection: PgPool, args: ProductsArgs) -> Result<(), ApplicationError> {
+    let products = report::list_products(
+        &connection,
+        &args.supermarket,
+        args.search.as_deref(),
+        args.limit,
+    )
+    .await?;
+
+    for product in products {
+        println!(
+            "{}\t{}\t{}\t{}",
+            product.id,
+            product.key,
+            product.name,
+            product.brand.unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints the prices recorded for a product.
+async fn prices(connection: PgPool, args: PricesArgs) -> Result<(), ApplicationError> {
+    let prices = report::get_prices(&connection, args.product_id, args.since).await?;
+    if prices.is_empty() {
+        tracing::warn!("No prices are recorded for product {}", args.product_id);
+    }
+
+    let dollars = |cents: i32| format!("${}.{:02}", cents / 100, cents % 100);
+    for price in prices {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            price.time,
+            price.supermarket,
+            price.store,
+            dollars(price.cost_in_cents),
+            price
+                .original_cost_in_cents
+                .map(dollars)
+                .unwrap_or_default(),
+            price.unit_price_in_cents.map(dollars).unwrap_or_default()
+        );
     }
+
+    Ok(())
+}
+
+/// Exports the recorded prices as CSV.
+async fn export(connection: PgPool, args: ExportArgs) -> Result<(), ApplicationError> {
+    let supermarket = args.supermarket.as_ref();
+    let count = match &args.output {
+        Some(path) => {
+            let file = File::create(path)
+                .change_context(ApplicationError::QueryError)
+                .attach_printable_lazy(|| format!("When creating {}", path.display()))?;
+            report::export_prices(&connection, supermarket, args.since, BufWriter::new(file))
+                .await?
+        }
+        None => {
+            report::export_prices(
+                &connection,
+                supermarket,
+                args.since,
+                BufWriter::new(io::stdout()),
+            )
+            .await?
+        }
+    };
+    tracing::info!("Exported {count} prices");
+
+    Ok(())
 }
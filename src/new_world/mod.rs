@@ -1,5 +0,0 @@
-mod run;
-
-pub use run::run;
-
-pub const NEW_WORLD_BASE_URL: &str = "https://www.newworld.co.nz/CommonApi";
@@ -0,0 +1,266 @@
+use std::{sync::Arc, time::Duration};
+
+use reqwest::{header::RETRY_AFTER, RequestBuilder, Response, StatusCode};
+use tokio::{sync::Mutex, time::Instant};
+
+use crate::supermarket::Supermarket;
+
+/// Describes how quickly requests may be made to a supermarket.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct RateLimitConfig {
+    /// The rate requests are initially made at.
+    pub requests_per_second: f64,
+    /// The slowest rate to back off to when being rate limited.
+    pub min_requests_per_second: f64,
+    /// The fastest rate to speed up to while requests are succeeding.
+    pub max_requests_per_second: f64,
+    /// The amount of requests that may be made at once after being idle.
+    pub burst: u32,
+    /// The amount of workers performing requests in parallel.
+    pub concurrent_requests: usize,
+}
+
+impl RateLimitConfig {
+    /// The default rate limits for a supermarket.
+    #[must_use]
+    pub fn for_supermarket(supermarket: &Supermarket) -> Self {
+        match supermarket {
+            // Countdown rate limits aggressively, so we start conservatively
+            Supermarket::Countdown => Self {
+                requests_per_second: 4.0,
+                min_requests_per_second: 0.5,
+                max_requests_per_second: 8.0,
+                burst: 2,
+                concurrent_requests: 2,
+            },
+            // Foodstuffs products are served by Algolia, which copes with
+            // more load
+            Supermarket::NewWorld | Supermarket::PakNSave => Self {
+                requests_per_second: 4.0,
+                min_requests_per_second: 0.5,
+                max_requests_per_second: 12.0,
+                burst: 4,
+                concurrent_requests: 4,
+            },
+        }
+    }
+}
+
+/// The mutable state of the token bucket.
+struct Bucket {
+    /// The amount of requests that can be made immediately.
+    tokens: f64,
+    /// The current rate tokens are added to the bucket.
+    rate: f64,
+    /// When tokens were last added to the bucket.
+    last_refill: Instant,
+    /// No requests are made until this time, if set.
+    paused_until: Option<Instant>,
+}
+
+/// A token bucket rate limiter, shared between every worker scraping a
+/// supermarket.
+///
+/// The rate is halved whenever the supermarket responds with
+/// `429 Too Many Requests`, honouring any `Retry-After` header, and slowly
+/// increased again while requests succeed.
+#[derive(Clone)]
+#[allow(clippy::module_name_repetitions)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: f64::from(config.burst),
+                rate: config.requests_per_second,
+                last_refill: Instant::now(),
+                paused_until: None,
+            })),
+        }
+    }
+
+    /// The amount of workers that should perform requests in parallel.
+    #[must_use]
+    pub fn concurrent_requests(&self) -> usize {
+        self.config.concurrent_requests
+    }
+
+    /// Waits until a request can be made.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+
+                match bucket.paused_until {
+                    Some(paused_until) if paused_until > now => paused_until - now,
+                    _ => {
+                        bucket.paused_until = None;
+
+                        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                        bucket.tokens = (bucket.tokens + elapsed * bucket.rate)
+                            .min(f64::from(self.config.burst));
+                        bucket.last_refill = now;
+
+                        if bucket.tokens >= 1.0 {
+                            bucket.tokens -= 1.0;
+                            return;
+                        }
+
+                        Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.rate)
+                    }
+                }
+            };
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Adjusts the rate based on the response the supermarket gave.
+    pub async fn record_response(&self, response: &Response) {
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+
+        self.record_status(response.status(), retry_after).await;
+    }
+
+    /// Adjusts the rate based on the `status` of a response, pausing for
+    /// `retry_after` if rate limited.
+    async fn record_status(&self, status: StatusCode, retry_after: Option<Duration>) {
+        let mut bucket = self.bucket.lock().await;
+
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            bucket.rate = (bucket.rate / 2.0).max(self.config.min_requests_per_second);
+            bucket.tokens = 0.0;
+
+            if let Some(retry_after) = retry_after {
+                bucket.paused_until = Some(Instant::now() + retry_after);
+            }
+
+            tracing::warn!(
+                "Rate limited, slowing down to {:.2} requests per second{}",
+                bucket.rate,
+                retry_after.map_or_else(String::new, |delay| format!(" after {delay:?}"))
+            );
+        } else if status.is_success() {
+            // increase additively, so we recover slowly after being rate limited
+            bucket.rate = (bucket.rate + self.config.requests_per_second / 20.0)
+                .min(self.config.max_requests_per_second);
+        }
+    }
+
+    /// Sends a request once the rate limit allows it, adjusting the rate from
+    /// the response.
+    ///
+    /// # Errors
+    /// - If unable to send the request
+    /// - If the response has an error status code
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        self.acquire().await;
+        let response = request.send().await?;
+        self.record_response(&response).await;
+
+        response.error_for_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limiter() -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            requests_per_second: 4.0,
+            min_requests_per_second: 0.5,
+            max_requests_per_second: 8.0,
+            burst: 2,
+            concurrent_requests: 1,
+        })
+    }
+
+    async fn rate(rate_limiter: &RateLimiter) -> f64 {
+        rate_limiter.bucket.lock().await.rate
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn allows_a_burst_then_waits_for_tokens() {
+        let rate_limiter = rate_limiter();
+        let start = Instant::now();
+
+        rate_limiter.acquire().await;
+        rate_limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        rate_limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(250));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn halves_the_rate_when_rate_limited() {
+        let rate_limiter = rate_limiter();
+
+        rate_limiter
+            .record_status(StatusCode::TOO_MANY_REQUESTS, None)
+            .await;
+        assert!((rate(&rate_limiter).await - 2.0).abs() < f64::EPSILON);
+
+        // the burst is spent, so the next request waits for a token
+        let start = Instant::now();
+        rate_limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+
+        for _ in 0..10 {
+            rate_limiter
+                .record_status(StatusCode::TOO_MANY_REQUESTS, None)
+                .await;
+        }
+        assert!((rate(&rate_limiter).await - 0.5).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn pauses_until_retry_after() {
+        let rate_limiter = rate_limiter();
+
+        rate_limiter
+            .record_status(StatusCode::TOO_MANY_REQUESTS, Some(Duration::from_secs(3)))
+            .await;
+
+        let start = Instant::now();
+        rate_limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_secs(3));
+        assert!(start.elapsed() < Duration::from_secs(4));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn recovers_to_the_max_rate_while_succeeding() {
+        let rate_limiter = rate_limiter();
+
+        rate_limiter
+            .record_status(StatusCode::TOO_MANY_REQUESTS, None)
+            .await;
+        rate_limiter.record_status(StatusCode::OK, None).await;
+        assert!((rate(&rate_limiter).await - 2.2).abs() < 1e-9);
+
+        for _ in 0..100 {
+            rate_limiter.record_status(StatusCode::OK, None).await;
+        }
+        assert!((rate(&rate_limiter).await - 8.0).abs() < f64::EPSILON);
+
+        // errors other than being rate limited do not change the rate
+        rate_limiter
+            .record_status(StatusCode::INTERNAL_SERVER_ERROR, None)
+            .await;
+        assert!((rate(&rate_limiter).await - 8.0).abs() < f64::EPSILON);
+    }
+}
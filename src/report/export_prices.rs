@@ -0,0 +1,118 @@
+use std::io::Write;
+
+use error_stack::{Result, ResultExt};
+use futures::TryStreamExt;
+use sqlx::PgPool;
+use time::Date;
+
+use crate::{error::ApplicationError, supermarket::Supermarket};
+
+/// The header row of an export.
+const HEADER: [&str; 11] = [
+    "time",
+    "supermarket",
+    "store",
+    "product_id",
+    "product_key",
+    "name",
+    "cost_in_cents",
+    "original_cost_in_cents",
+    "savings_in_cents",
+    "unit_price_in_cents",
+    "run_id",
+];
+
+/// Quotes a CSV field if it contains characters which would otherwise break
+/// the row.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes every recorded price into `writer` as CSV, oldest first, returning
+/// the amount of prices written.
+///
+/// If `supermarket` is given, only the prices of its stores are exported. If
+/// `since` is given, only prices recorded on or after the date (in UTC) are
+/// exported.
+///
+/// Prices are streamed from the database, so the export does not need to fit
+/// in memory.
+///
+/// # Errors
+/// - If unable to query the database
+/// - If unable to write to `writer`
+#[tracing::instrument(name = "export prices", level = "debug", skip(pool, writer))]
+pub async fn export_prices<W: Write + Send>(
+    pool: &PgPool,
+    supermarket: Option<&Supermarket>,
+    since: Option<Date>,
+    mut writer: W,
+) -> Result<u64, ApplicationError> {
+    writeln!(writer, "{}", HEADER.join(",")).change_context(ApplicationError::QueryError)?;
+
+    let mut rows = sqlx::query!(
+        r#"SELECT
+			to_char(prices.time AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS "time!",
+			stores.supermarket::text AS "supermarket!",
+			COALESCE(
+				stores.countdown_store_id::text,
+				stores.new_world_store_id,
+				stores.paknsave_store_id
+			) AS "store!",
+			prices.product_id,
+			COALESCE(countdown_products.sku, foodstuffs_products.product_id) AS "product_key!",
+			COALESCE(countdown_products.name, foodstuffs_products.name) AS "name!",
+			prices.cost_in_cents,
+			prices.original_cost_in_cents,
+			prices.savings_in_cents,
+			prices.unit_price_in_cents,
+			prices.run_id
+		FROM prices
+			INNER JOIN stores ON stores.id = prices.store_id
+			INNER JOIN products ON products.id = prices.product_id
+			LEFT JOIN countdown_products ON countdown_products.id = products.countdown_id
+			LEFT JOIN foodstuffs_products ON foodstuffs_products.id = products.foodstuffs_id
+		WHERE ($1::text IS NULL OR stores.supermarket = $1::text::supermarket)
+			AND ($2::text IS NULL OR prices.time >= $2::text::date AT TIME ZONE 'UTC')
+		ORDER BY prices.time, prices.id"#,
+        supermarket.map(ToString::to_string),
+        since.map(|since| since.to_string())
+    )
+    .fetch(pool);
+
+    let optional = |value: Option<i32>| value.map(|value| value.to_string()).unwrap_or_default();
+    let mut count = 0;
+    while let Some(row) = rows
+        .try_next()
+        .await
+        .change_context(ApplicationError::QueryError)?
+    {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            row.time,
+            escape(&row.supermarket),
+            escape(&row.store),
+            row.product_id,
+            escape(&row.product_key),
+            escape(&row.name),
+            row.cost_in_cents,
+            optional(row.original_cost_in_cents),
+            optional(row.savings_in_cents),
+            optional(row.unit_price_in_cents),
+            optional(row.run_id),
+        )
+        .change_context(ApplicationError::QueryError)?;
+        count += 1;
+    }
+
+    writer
+        .flush()
+        .change_context(ApplicationError::QueryError)?;
+
+    Ok(count)
+}
@@ -0,0 +1,57 @@
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+use time::Date;
+
+use crate::error::ApplicationError;
+
+/// A price of a product recorded at a store.
+pub struct RecordedPrice {
+    /// When the price was recorded, in RFC 3339 format.
+    pub time: String,
+    /// The name of the supermarket, e.g., `Countdown`.
+    pub supermarket: String,
+    /// The identifier the supermarket uses for the store.
+    pub store: String,
+    pub cost_in_cents: i32,
+    pub original_cost_in_cents: Option<i32>,
+    pub unit_price_in_cents: Option<i32>,
+}
+
+/// Retrieves the prices recorded for a product at every store, oldest first.
+///
+/// If `since` is given, only prices recorded on or after the date (in UTC)
+/// are retrieved.
+///
+/// # Errors
+/// If unable to query the database.
+#[tracing::instrument(name = "get prices", level = "debug", skip(pool))]
+pub async fn get_prices(
+    pool: &PgPool,
+    product_id: i32,
+    since: Option<Date>,
+) -> Result<Vec<RecordedPrice>, ApplicationError> {
+    sqlx::query_as!(
+        RecordedPrice,
+        r#"SELECT
+			to_char(prices.time AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"') AS "time!",
+			stores.supermarket::text AS "supermarket!",
+			COALESCE(
+				stores.countdown_store_id::text,
+				stores.new_world_store_id,
+				stores.paknsave_store_id
+			) AS "store!",
+			prices.cost_in_cents,
+			prices.original_cost_in_cents,
+			prices.unit_price_in_cents
+		FROM prices
+			INNER JOIN stores ON stores.id = prices.store_id
+		WHERE prices.product_id = $1
+			AND ($2::text IS NULL OR prices.time >= $2::text::date AT TIME ZONE 'UTC')
+		ORDER BY prices.time"#,
+        product_id,
+        since.map(|since| since.to_string())
+    )
+    .fetch_all(pool)
+    .await
+    .change_context(ApplicationError::QueryError)
+}
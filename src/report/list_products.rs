@@ -0,0 +1,55 @@
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+
+use crate::{error::ApplicationError, supermarket::Supermarket};
+
+/// A product that prices are tracked for.
+pub struct TrackedProduct {
+    /// The id of the product in the `products` table.
+    pub id: i32,
+    /// The identifier the supermarket uses for the product, such as a sku.
+    pub key: String,
+    pub name: String,
+    pub brand: Option<String>,
+}
+
+/// Lists the products tracked for a supermarket, ordered by name.
+///
+/// New World and Pak'nSave share their products, so listing either lists the
+/// products of both. If `search` is given, only products with a name
+/// containing it (ignoring case) are listed.
+///
+/// # Errors
+/// If unable to query the database.
+#[tracing::instrument(name = "list products", level = "debug", skip(pool))]
+pub async fn list_products(
+    pool: &PgPool,
+    supermarket: &Supermarket,
+    search: Option<&str>,
+    limit: i64,
+) -> Result<Vec<TrackedProduct>, ApplicationError> {
+    sqlx::query_as!(
+        TrackedProduct,
+        r#"SELECT
+			products.id,
+			COALESCE(countdown_products.sku, foodstuffs_products.product_id) AS "key!",
+			COALESCE(countdown_products.name, foodstuffs_products.name) AS "name!",
+			COALESCE(countdown_products.brand, foodstuffs_products.brand) AS brand
+		FROM products
+			LEFT JOIN countdown_products ON countdown_products.id = products.countdown_id
+			LEFT JOIN foodstuffs_products ON foodstuffs_products.id = products.foodstuffs_id
+		WHERE (CASE WHEN $1 THEN products.countdown_id ELSE products.foodstuffs_id END) IS NOT NULL
+			AND (
+				$2::text IS NULL
+				OR COALESCE(countdown_products.name, foodstuffs_products.name) ILIKE '%' || $2 || '%'
+			)
+		ORDER BY 3
+		LIMIT $3"#,
+        *supermarket == Supermarket::Countdown,
+        search,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+    .change_context(ApplicationError::QueryError)
+}
@@ -0,0 +1,7 @@
+mod export_prices;
+mod get_prices;
+mod list_products;
+
+pub use export_prices::export_prices;
+pub use get_prices::{get_prices, RecordedPrice};
+pub use list_products::{list_products, TrackedProduct};
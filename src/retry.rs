@@ -0,0 +1,115 @@
+use std::{future::Future, time::Duration};
+
+use rand::Rng;
+use reqwest::StatusCode;
+
+/// Describes how HTTP requests that fail with a transient error are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct RetryConfig {
+    /// The maximum amount of times to retry a request, after the first attempt.
+    pub max_retries: u32,
+    /// The delay before the first retry, which doubles after each retry.
+    pub base_delay: Duration,
+    /// The maximum delay between retries.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// The delay to wait before the retry numbered `attempt` (starting at 0).
+    ///
+    /// Uses "full jitter", picking a random delay between zero and the
+    /// exponential backoff, so concurrent requests do not retry in lockstep.
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+
+        backoff.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+    }
+}
+
+/// If the error is likely to succeed when the request is retried.
+///
+/// This is the case for timeouts, connection failures, server errors (5xx)
+/// and being rate limited (429).
+fn is_transient(error: &reqwest::Error) -> bool {
+    if error.is_timeout() || error.is_connect() {
+        return true;
+    }
+
+    error
+        .status()
+        .is_some_and(|status| status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS)
+}
+
+/// Performs an HTTP `operation`, retrying it with exponential backoff when it
+/// fails with a transient error.
+///
+/// The operation should call [`reqwest::Response::error_for_status`], so
+/// server errors and rate limiting are surfaced as errors.
+///
+/// # Errors
+/// - If the operation fails with an error that is not transient
+/// - If the operation still fails after [`RetryConfig::max_retries`] retries
+pub async fn retry<T, F, Fut>(config: &RetryConfig, mut operation: F) -> Result<T, reqwest::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, reqwest::Error>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(error) if attempt < config.max_retries && is_transient(&error) => {
+                let delay = config.delay(attempt);
+                attempt += 1;
+                tracing::warn!(
+                    "Request failed, retrying in {delay:?} (attempt {attempt} of {}): {error}",
+                    config.max_retries
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_are_jittered_within_the_max_delay() {
+        let config = RetryConfig {
+            max_retries: 64,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+        };
+
+        for attempt in 0..config.max_retries {
+            let backoff = config
+                .base_delay
+                .saturating_mul(2u32.saturating_pow(attempt))
+                .min(config.max_delay);
+
+            for _ in 0..100 {
+                let delay = config.delay(attempt);
+                assert!(delay <= backoff, "{delay:?} exceeds {backoff:?}");
+                assert!(delay <= config.max_delay);
+            }
+        }
+    }
+}
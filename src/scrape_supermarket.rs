@@ -0,0 +1,92 @@
+use error_stack::Result;
+use sqlx::PgPool;
+
+use crate::{
+    config::ApplicationConfig,
+    countdown::CountdownScraper,
+    error::ApplicationError,
+    foodstuffs::{NewWorldScraper, PakNSaveScraper},
+    scraper::{self, Scraper, Shutdown},
+    supermarket::Supermarket,
+};
+
+/// Scrapes the configured supermarket, or replays a cache or re-parses an
+/// archive of it if configured.
+///
+/// Scraping stops early once `shutdown` is requested, as described by
+/// [`Shutdown`].
+///
+/// # Errors
+/// - If unable to create the scraper of the supermarket
+/// - If unable to scrape, replay or re-parse the supermarket
+pub async fn scrape_supermarket(
+    connection: PgPool,
+    config: &ApplicationConfig,
+    shutdown: &Shutdown,
+) -> Result<(), ApplicationError> {
+    let retry = config.retry;
+    let rate_limit = config.rate_limit;
+
+    match config.supermarket {
+        Supermarket::Countdown => {
+            scrape(
+                CountdownScraper::new(retry, rate_limit)?,
+                connection,
+                config,
+                shutdown,
+            )
+            .await
+        }
+        Supermarket::NewWorld => {
+            scrape(
+                NewWorldScraper::new(rate_limit)?,
+                connection,
+                config,
+                shutdown,
+            )
+            .await
+        }
+        Supermarket::PakNSave => {
+            scrape(
+                PakNSaveScraper::new(rate_limit)?,
+                connection,
+                config,
+                shutdown,
+            )
+            .await
+        }
+    }
+}
+
+/// Scrapes the supermarket, or replays a cache or re-parses an archive of it
+/// if configured.
+async fn scrape<S: Scraper>(
+    scraper: S,
+    connection: PgPool,
+    config: &ApplicationConfig,
+    shutdown: &Shutdown,
+) -> Result<(), ApplicationError> {
+    let should_insert = config.should_insert;
+
+    match (&config.replay, &config.reparse) {
+        (Some(path), _) => {
+            scraper::replay(scraper, connection, path, &config.stores, should_insert).await
+        }
+        (None, Some(path)) => {
+            scraper::reparse(scraper, connection, path, &config.stores, should_insert).await
+        }
+        (None, None) => {
+            scraper::run(
+                scraper,
+                connection,
+                &config.stores,
+                should_insert,
+                config.price_storage,
+                config.resume,
+                &config.cache,
+                shutdown,
+            )
+            .await
+        }
+    }
+}
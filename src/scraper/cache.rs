@@ -0,0 +1,261 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use error_stack::{Result, ResultExt};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{de::DeserializeOwned, Serialize};
+use time::{macros::format_description, OffsetDateTime};
+
+use crate::{error::ApplicationError, supermarket::Supermarket};
+
+/// Describes where the products of each scraped store are cached, and where
+/// raw responses are archived.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub struct CacheConfig {
+    /// The directory caches are written into, as
+    /// `<directory>/<supermarket>/<store>/<timestamp>.json`.
+    pub directory: PathBuf,
+    /// If caches should be compressed with gzip.
+    pub compress: bool,
+    /// How long caches are kept before being deleted, or `None` to keep
+    /// caches forever.
+    pub retention: Option<Duration>,
+    /// The directory the raw responses of each scrape are archived into, as
+    /// `<directory>/<supermarket>/<store>/<timestamp>/`, or `None` to not
+    /// archive responses.
+    pub archive_directory: Option<PathBuf>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("cache"),
+            compress: false,
+            retention: Some(Duration::from_hours(30 * 24)),
+            archive_directory: None,
+        }
+    }
+}
+
+/// The name of the directory caches of the supermarket are written into.
+fn supermarket_directory(supermarket: &Supermarket) -> &'static str {
+    match supermarket {
+        Supermarket::Countdown => "countdown",
+        Supermarket::NewWorld => "new_world",
+        Supermarket::PakNSave => "paknsave",
+    }
+}
+
+/// Replaces any characters of a key, such as a store id, which are not safe in
+/// a file name.
+pub(super) fn sanitize(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// The directory within `root` that files of the store are written into, as
+/// `<root>/<supermarket>/<store>`.
+pub(super) fn store_directory(root: &Path, supermarket: &Supermarket, store_key: &str) -> PathBuf {
+    root.join(supermarket_directory(supermarket))
+        .join(sanitize(store_key))
+}
+
+/// The current time, formatted to be used in a file name.
+pub(super) fn file_timestamp() -> std::result::Result<String, time::error::Format> {
+    OffsetDateTime::now_utc().format(format_description!(
+        "[year][month][day]T[hour][minute][second]Z"
+    ))
+}
+
+/// Writes the products scraped from a store into a new cache file, returning
+/// the path of the file.
+///
+/// Once written, caches of the store older than the retention period are
+/// deleted.
+///
+/// # Errors
+/// - If unable to create the cache directory or file
+/// - If unable to encode the products
+pub fn write_cache<P: Serialize>(
+    config: &CacheConfig,
+    supermarket: &Supermarket,
+    store_key: &str,
+    products: &[P],
+) -> Result<PathBuf, ApplicationError> {
+    let directory = store_directory(&config.directory, supermarket, store_key);
+    fs::create_dir_all(&directory)
+        .change_context(ApplicationError::CacheError)
+        .attach_printable_lazy(|| format!("When creating {}", directory.display()))?;
+
+    let timestamp = file_timestamp().change_context(ApplicationError::CacheError)?;
+    let extension = if config.compress { "json.gz" } else { "json" };
+    let path = directory.join(format!("{timestamp}.{extension}"));
+
+    let file = File::create(&path)
+        .change_context(ApplicationError::CacheError)
+        .attach_printable_lazy(|| format!("When creating {}", path.display()))?;
+    if config.compress {
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+        serde_json::to_writer(&mut encoder, products)
+            .change_context(ApplicationError::CacheError)?;
+        encoder
+            .finish()
+            .and_then(|mut writer| writer.flush())
+            .change_context(ApplicationError::CacheError)?;
+    } else {
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, products)
+            .change_context(ApplicationError::CacheError)?;
+        writer
+            .flush()
+            .change_context(ApplicationError::CacheError)?;
+    }
+
+    if let Some(retention) = config.retention {
+        prune_cache(&directory, retention)?;
+    }
+
+    Ok(path)
+}
+
+/// If the file at `path` is a cache written by [`write_cache`], judging by its
+/// extension.
+fn is_cache_file(path: &Path) -> bool {
+    let uncompressed = if path.extension().is_some_and(|extension| extension == "gz") {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    };
+
+    path.is_file()
+        && uncompressed
+            .extension()
+            .is_some_and(|extension| extension == "json")
+}
+
+/// Deletes every cache file in `directory` last modified longer than
+/// `retention` ago.
+///
+/// Files that are not caches, such as files added to the directory by hand,
+/// are never deleted.
+///
+/// # Errors
+/// If unable to read the directory, or delete a cache file.
+fn prune_cache(directory: &Path, retention: Duration) -> Result<(), ApplicationError> {
+    let Some(cutoff) = SystemTime::now().checked_sub(retention) else {
+        return Ok(());
+    };
+
+    let entries = fs::read_dir(directory)
+        .change_context(ApplicationError::CacheError)
+        .attach_printable_lazy(|| format!("When reading {}", directory.display()))?;
+    for entry in entries {
+        let entry = entry.change_context(ApplicationError::CacheError)?;
+        if !is_cache_file(&entry.path()) {
+            continue;
+        }
+
+        let modified = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .change_context(ApplicationError::CacheError)?;
+
+        if modified < cutoff {
+            tracing::debug!("Deleting expired cache {}", entry.path().display());
+            fs::remove_file(entry.path())
+                .change_context(ApplicationError::CacheError)
+                .attach_printable_lazy(|| format!("When deleting {}", entry.path().display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the products from a cache file written by [`write_cache`].
+///
+/// Files ending in `.gz` are decompressed.
+///
+/// # Errors
+/// If unable to read or decode the file.
+pub fn read_cache<P: DeserializeOwned>(path: &Path) -> Result<Vec<P>, ApplicationError> {
+    let file = File::open(path)
+        .change_context(ApplicationError::CacheError)
+        .attach_printable_lazy(|| format!("When reading {}", path.display()))?;
+
+    let mut contents = String::new();
+    if path.extension().is_some_and(|extension| extension == "gz") {
+        GzDecoder::new(BufReader::new(file)).read_to_string(&mut contents)
+    } else {
+        BufReader::new(file).read_to_string(&mut contents)
+    }
+    .change_context(ApplicationError::CacheError)
+    .attach_printable_lazy(|| format!("When reading {}", path.display()))?;
+
+    serde_json::from_str(&contents)
+        .change_context(ApplicationError::CacheError)
+        .attach_printable_lazy(|| format!("When decoding {}", path.display()))
+}
+
+/// The id of the store a cache file of the supermarket was written for, if
+/// the file is within the directory layout used by [`write_cache`].
+///
+/// Response archives share this layout, so this also finds the store of an
+/// archive directory.
+#[must_use]
+pub fn cached_store_key(path: &Path, supermarket: &Supermarket) -> Option<String> {
+    let store_directory = path.parent()?;
+    if store_directory.parent()?.file_name()? != supermarket_directory(supermarket) {
+        return None;
+    }
+
+    store_directory
+        .file_name()?
+        .to_str()
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a file in `directory`, last modified `age` ago.
+    fn create_file(directory: &Path, name: &str, age: Duration) -> PathBuf {
+        let path = directory.join(name);
+        File::create(&path)
+            .and_then(|file| file.set_modified(SystemTime::now() - age))
+            .unwrap();
+
+        path
+    }
+
+    #[test]
+    fn only_prunes_expired_caches() {
+        let directory = tempfile::tempdir().unwrap();
+        let day = Duration::from_hours(24);
+
+        let expired = create_file(directory.path(), "20240101T000000Z.json", day * 10);
+        let expired_compressed =
+            create_file(directory.path(), "20240102T000000Z.json.gz", day * 10);
+        let recent = create_file(directory.path(), "20240110T000000Z.json", Duration::ZERO);
+        let notes = create_file(directory.path(), "notes.txt", day * 10);
+
+        prune_cache(directory.path(), day * 7).unwrap();
+
+        assert!(!expired.exists());
+        assert!(!expired_compressed.exists());
+        assert!(recent.exists());
+        assert!(notes.exists());
+    }
+}
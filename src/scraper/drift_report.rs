@@ -0,0 +1,84 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::Serialize;
+
+/// Describes the parts of a supermarket's responses that the decoders did not
+/// recognise, so changes to the API are noticed before prices go missing.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct DriftReport {
+    /// The amount of items skipped for each unknown item type.
+    pub unknown_types: BTreeMap<String, usize>,
+    /// The amount of times each field the decoders do not read was seen,
+    /// keyed by its path, e.g., `products.items[].images`.
+    pub unknown_fields: BTreeMap<String, usize>,
+}
+
+impl DriftReport {
+    /// If the responses only contained what the decoders expected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.unknown_types.is_empty() && self.unknown_fields.is_empty()
+    }
+
+    /// Records that an item of an unknown type was skipped.
+    pub fn unknown_type(&mut self, kind: &str) {
+        *self.unknown_types.entry(kind.to_string()).or_default() += 1;
+    }
+
+    /// Records a field that the decoders ignored, at `path` within the value
+    /// found at `parent` (or the root of the response if empty).
+    ///
+    /// Array indices in the `path` are replaced with `[]`, so a field is
+    /// reported once no matter how many items it appeared in. Fields whose
+    /// path is in `known` are always sent by the supermarket and deliberately
+    /// not decoded, so are not recorded.
+    pub fn unknown_field(&mut self, parent: &str, path: &serde_ignored::Path, known: &[&str]) {
+        let path = match normalize(path) {
+            path if parent.is_empty() => path,
+            path => format!("{parent}.{path}"),
+        };
+        if known.contains(&path.as_str()) {
+            return;
+        }
+
+        *self.unknown_fields.entry(path).or_default() += 1;
+    }
+
+    /// Adds every occurrence recorded in `other` to this report.
+    pub fn merge(&mut self, other: DriftReport) {
+        for (kind, count) in other.unknown_types {
+            *self.unknown_types.entry(kind).or_default() += count;
+        }
+        for (field, count) in other.unknown_fields {
+            *self.unknown_fields.entry(field).or_default() += count;
+        }
+    }
+}
+
+impl fmt::Display for DriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (kind, count) in &self.unknown_types {
+            writeln!(f, "- {count} items of unknown type '{kind}'")?;
+        }
+        for (field, count) in &self.unknown_fields {
+            writeln!(f, "- unexpected field '{field}' ({count} times)")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats a path to an ignored field, omitting array indices.
+fn normalize(path: &serde_ignored::Path) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, .. } => format!("{}[]", normalize(parent)),
+        serde_ignored::Path::Map { parent, key } => match normalize(parent) {
+            parent if parent.is_empty() => key.clone(),
+            parent => format!("{parent}.{key}"),
+        },
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => normalize(parent),
+    }
+}
@@ -0,0 +1,27 @@
+use std::collections::{HashMap, HashSet};
+
+use super::ScrapedProduct;
+
+/// Computes all the product keys that are now off-sale against historically
+/// known product keys, by comparing against a list of known products that are
+/// currently on sale.
+///
+/// `stored_product_ids` is every product known to the database, as returned
+/// by [`super::Scraper::get_product_ids`].
+#[allow(clippy::implicit_hasher)]
+pub fn get_off_sale_products<P: ScrapedProduct>(
+    stored_product_ids: &HashMap<String, i32>,
+    fetched_products: &[P],
+) -> Vec<String> {
+    // now compare with the products we fetched
+    let fetched_product_keys = fetched_products
+        .iter()
+        .map(ScrapedProduct::key)
+        .collect::<HashSet<_>>();
+
+    stored_product_ids
+        .keys()
+        .filter(|key| !fetched_product_keys.contains(key.as_str()))
+        .cloned()
+        .collect()
+}
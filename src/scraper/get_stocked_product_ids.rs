@@ -0,0 +1,35 @@
+use std::collections::HashSet;
+
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+
+use crate::{error::ApplicationError, supermarket::Supermarket};
+
+/// Retrieves the ids of every product that has been priced at a store of the
+/// `supermarket`.
+///
+/// Products can be shared between supermarkets, as the Foodstuffs banners
+/// share product identifiers, so a product known to the database is not
+/// necessarily sold by the supermarket.
+///
+/// # Errors
+/// If unable to retrieve the products from the database
+#[tracing::instrument(name = "get stocked product ids", level = "debug", skip_all, fields(%supermarket))]
+pub async fn get_stocked_product_ids(
+    pool: &PgPool,
+    supermarket: &Supermarket,
+) -> Result<HashSet<i32>, ApplicationError> {
+    let product_ids = sqlx::query_scalar!(
+        r"SELECT DISTINCT latest_prices.product_id FROM latest_prices
+			INNER JOIN stores ON stores.id = latest_prices.store_id
+			WHERE stores.supermarket = $1::text::supermarket",
+        supermarket.to_string()
+    )
+    .fetch_all(pool)
+    .await
+    .change_context(ApplicationError::ProductRetrieval)?
+    .into_iter()
+    .collect();
+
+    Ok(product_ids)
+}
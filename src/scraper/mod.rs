@@ -0,0 +1,190 @@
+mod cache;
+mod drift_report;
+mod get_off_sale_products;
+mod get_stocked_product_ids;
+mod page_queue;
+mod price;
+mod product_fetch;
+mod promotion;
+mod reparse;
+mod replay;
+mod response_archive;
+mod run;
+mod save_categories;
+mod save_prices;
+mod save_promotions;
+mod save_scraped_products;
+mod scrape_run;
+mod select_stores;
+mod shutdown;
+mod store_lock;
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    future::Future,
+};
+
+use error_stack::{Report, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::PgPool;
+
+use crate::{error::ApplicationError, supermarket::Supermarket};
+
+pub use cache::{cached_store_key, read_cache, write_cache, CacheConfig};
+pub use drift_report::DriftReport;
+pub use get_off_sale_products::get_off_sale_products;
+pub use get_stocked_product_ids::get_stocked_product_ids;
+pub use page_queue::{PageQueue, QueueProgress, QueuedPage};
+pub use price::{to_cents, Price};
+pub use product_fetch::{FailedPage, ProductFetch};
+pub use promotion::{Promotion, PromotionKind};
+pub use reparse::reparse;
+pub use replay::replay;
+pub use response_archive::{read_archive, ArchivedResponse, ResponseArchive};
+pub use run::run;
+pub use save_categories::save_categories;
+pub use save_prices::{save_prices, PriceStorage};
+pub use save_promotions::save_promotions;
+pub use save_scraped_products::save_scraped_products;
+pub use scrape_run::{
+    finish_scrape_run, get_scrape_run, resume_scrape_run, save_failed_pages, start_scrape_run,
+    ScrapeRun, ScrapeRunOutcome,
+};
+pub use select_stores::select_stores;
+pub use shutdown::{Shutdown, ShutdownTrigger};
+pub use store_lock::{lock_store, StoreLock};
+
+/// A product that has been retrieved from a supermarket.
+pub trait ScrapedProduct {
+    /// The identifier the supermarket uses for the product, such as a sku.
+    fn key(&self) -> &str;
+    /// The name of the product.
+    fn name(&self) -> &str;
+    /// The names of the categories the product belongs to.
+    fn categories(&self) -> &BTreeSet<String>;
+    /// The current price of the product.
+    fn price(&self) -> Price;
+    /// The promotions currently available for the product.
+    fn promotions(&self) -> &[Promotion] {
+        &[]
+    }
+    /// The current price of one unit (e.g., 1kg) of the product, in cents.
+    ///
+    /// `None` if the supermarket does not provide a unit price.
+    fn unit_price(&self) -> Option<i32> {
+        None
+    }
+}
+
+/// A physical store belonging to a supermarket chain.
+pub trait ScrapedStore {
+    /// The identifier the supermarket uses for the store.
+    fn id(&self) -> String;
+    /// The name of the store.
+    fn name(&self) -> &str;
+}
+
+/// Describes how to retrieve and store the prices of a supermarket chain.
+///
+/// Each supermarket only implements the parts that are specific to its API
+/// and its tables, while [`run`] drives the scrape, caches the results,
+/// detects off-sale products and saves prices.
+pub trait Scraper: Send + Sync {
+    /// A store that products can be fetched for.
+    type Store: ScrapedStore + Send + Sync;
+    /// A category of products that can be browsed.
+    type Category: Display + Send;
+    /// A product that can be purchased.
+    type Product: ScrapedProduct + Serialize + DeserializeOwned + Send;
+
+    /// The supermarket this scraper retrieves prices for.
+    const SUPERMARKET: Supermarket;
+    /// The ids or names of the stores to retrieve prices from when no stores
+    /// are configured.
+    const DEFAULT_STORES: &'static [&'static str];
+
+    /// Lists every known store of the supermarket.
+    fn list_stores(
+        &self,
+        pool: &PgPool,
+    ) -> impl Future<Output = Result<Vec<Self::Store>, ApplicationError>> + Send;
+
+    /// Selects the store that subsequent requests should retrieve information for.
+    fn select_store(
+        &self,
+        store: &Self::Store,
+    ) -> impl Future<Output = Result<(), ApplicationError>> + Send;
+
+    /// Lists all the categories of products at the store.
+    ///
+    /// Scrapers which can re-parse their responses archive them into
+    /// `archive`.
+    fn list_categories(
+        &self,
+        store: &Self::Store,
+        archive: &ResponseArchive,
+    ) -> impl Future<Output = Result<Vec<Self::Category>, ApplicationError>> + Send;
+
+    /// Fetches all the products in the given categories at the store.
+    ///
+    /// Pages that fail to be retrieved are skipped and reported in the
+    /// [`ProductFetch`], rather than failing the whole fetch.
+    ///
+    /// Pages are tracked in the `queue`, so that if it has progress from a
+    /// previous attempt, only the pending pages are retrieved.
+    ///
+    /// Scrapers which can re-parse their responses archive each page into
+    /// `archive`.
+    fn fetch_products(
+        &self,
+        store: &Self::Store,
+        categories: Vec<Self::Category>,
+        queue: &PageQueue,
+        archive: &ResponseArchive,
+    ) -> impl Future<Output = Result<ProductFetch<Self::Product>, ApplicationError>> + Send;
+
+    /// Rebuilds the products of a store from the raw responses of an archive,
+    /// using the current decoders.
+    ///
+    /// Pages which can no longer be decoded are reported in the
+    /// [`ProductFetch`]. By default responses are not archived, so they
+    /// cannot be re-parsed.
+    ///
+    /// # Errors
+    /// If the scraper does not archive its responses.
+    fn parse_archive(
+        &self,
+        _responses: &[ArchivedResponse],
+    ) -> Result<ProductFetch<Self::Product>, ApplicationError> {
+        Err(
+            Report::new(ApplicationError::ArchiveError).attach_printable(format!(
+                "Responses of {} are not archived, so cannot be re-parsed",
+                Self::SUPERMARKET
+            )),
+        )
+    }
+
+    /// Saves any products that have not been seen before into the database.
+    fn save_products(
+        &self,
+        pool: &PgPool,
+        products: &[Self::Product],
+    ) -> impl Future<Output = Result<(), ApplicationError>> + Send;
+
+    /// Saves the store into the database if it has not been seen before,
+    /// returning the id of the row in the `stores` table.
+    fn save_store(
+        &self,
+        pool: &PgPool,
+        store: &Self::Store,
+    ) -> impl Future<Output = Result<i32, ApplicationError>> + Send;
+
+    /// Retrieves every product of this supermarket stored in the database,
+    /// as a map from the product's [`ScrapedProduct::key`] to its id in the
+    /// `products` table.
+    fn get_product_ids(
+        &self,
+        pool: &PgPool,
+    ) -> impl Future<Output = Result<HashMap<String, i32>, ApplicationError>> + Send;
+}
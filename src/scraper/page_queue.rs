@@ -0,0 +1,187 @@
+use error_stack::{Result, ResultExt};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::PgPool;
+
+use crate::error::ApplicationError;
+
+/// A page of products in a category which needs to be retrieved.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueuedPage {
+    /// The name of the category the page belongs to.
+    pub category: String,
+    /// The page number, as used by the supermarket's API.
+    pub page: i64,
+}
+
+/// The progress of a scrape run that was previously interrupted.
+pub struct QueueProgress<P> {
+    /// The pages which are yet to be retrieved.
+    pub pending: Vec<QueuedPage>,
+    /// The products of the pages that were already retrieved.
+    pub products: Vec<P>,
+}
+
+/// The queue of pages a scrape run needs to retrieve.
+///
+/// When persisted, each page is stored in the `scrape_run_pages` table, along
+/// with its products once retrieved, so that an interrupted run can resume
+/// where it stopped. Otherwise, the queue is only held in memory by the
+/// scraper, and every operation does nothing.
+#[derive(Clone)]
+pub struct PageQueue {
+    /// The connection and id of the run the queue is persisted for.
+    run: Option<(PgPool, i32)>,
+}
+
+impl PageQueue {
+    /// A queue which is not persisted, and so cannot be resumed.
+    #[must_use]
+    pub fn in_memory() -> Self {
+        Self { run: None }
+    }
+
+    /// A queue persisted in the database for the run `run_id`.
+    #[must_use]
+    pub fn persisted(pool: PgPool, run_id: i32) -> Self {
+        Self {
+            run: Some((pool, run_id)),
+        }
+    }
+
+    /// Loads the progress of the run, or `None` if the run has not queued any
+    /// pages before.
+    ///
+    /// # Errors
+    /// - If unable to read the pages from the database
+    /// - If unable to decode the products of a retrieved page
+    #[tracing::instrument(name = "load page queue", level = "debug", skip_all)]
+    pub async fn progress<P: DeserializeOwned>(
+        &self,
+    ) -> Result<Option<QueueProgress<P>>, ApplicationError> {
+        let Some((pool, run_id)) = &self.run else {
+            return Ok(None);
+        };
+
+        let pages = sqlx::query!(
+            r#"SELECT category, page, products::text AS "products?"
+			FROM scrape_run_pages
+			WHERE run_id = $1"#,
+            run_id
+        )
+        .fetch_all(pool)
+        .await
+        .change_context(ApplicationError::WorkQueueError)?;
+
+        if pages.is_empty() {
+            return Ok(None);
+        }
+
+        let mut progress = QueueProgress {
+            pending: Vec::new(),
+            products: Vec::new(),
+        };
+        for page in pages {
+            match page.products {
+                Some(products) => progress.products.extend(
+                    serde_json::from_str::<Vec<P>>(&products)
+                        .change_context(ApplicationError::WorkQueueError)
+                        .attach_printable_lazy(|| {
+                            format!("When decoding page {} of '{}'", page.page, page.category)
+                        })?,
+                ),
+                None => progress.pending.push(QueuedPage {
+                    category: page.category,
+                    page: page.page,
+                }),
+            }
+        }
+
+        Ok(Some(progress))
+    }
+
+    /// Adds pages to the queue, ignoring any that are already queued.
+    ///
+    /// # Errors
+    /// If unable to insert the pages into the database.
+    #[tracing::instrument(name = "enqueue pages", level = "trace", skip_all, fields(count = pages.len()))]
+    pub async fn enqueue(&self, pages: &[QueuedPage]) -> Result<(), ApplicationError> {
+        let Some((pool, run_id)) = &self.run else {
+            return Ok(());
+        };
+
+        let (categories, page_numbers): (Vec<_>, Vec<_>) = pages
+            .iter()
+            .map(|page| (page.category.clone(), page.page))
+            .unzip();
+
+        sqlx::query!(
+            r"INSERT INTO scrape_run_pages (run_id, category, page)
+			SELECT $1, * FROM UNNEST($2::text[], $3::bigint[])
+			ON CONFLICT DO NOTHING",
+            run_id,
+            &categories,
+            &page_numbers
+        )
+        .execute(pool)
+        .await
+        .change_context(ApplicationError::WorkQueueError)?;
+
+        Ok(())
+    }
+
+    /// Marks a page as retrieved, storing the products that were found on it.
+    ///
+    /// # Errors
+    /// - If unable to encode the products
+    /// - If unable to update the page in the database
+    #[tracing::instrument(
+        name = "complete page",
+        level = "trace",
+        skip_all,
+        fields(category = %page.category, page = %page.page)
+    )]
+    pub async fn complete<P: Serialize>(
+        &self,
+        page: &QueuedPage,
+        products: &[P],
+    ) -> Result<(), ApplicationError> {
+        let Some((pool, run_id)) = &self.run else {
+            return Ok(());
+        };
+
+        let products =
+            serde_json::to_string(products).change_context(ApplicationError::WorkQueueError)?;
+
+        sqlx::query!(
+            r"UPDATE scrape_run_pages SET products = $4::text::jsonb
+			WHERE run_id = $1 AND category = $2 AND page = $3",
+            run_id,
+            page.category,
+            page.page,
+            products
+        )
+        .execute(pool)
+        .await
+        .change_context(ApplicationError::WorkQueueError)?;
+
+        Ok(())
+    }
+
+    /// Removes every page of the run, once it no longer needs to be resumed.
+    ///
+    /// # Errors
+    /// If unable to delete the pages from the database.
+    #[tracing::instrument(name = "clear page queue", level = "debug", skip_all)]
+    pub async fn clear(&self) -> Result<(), ApplicationError> {
+        let Some((pool, run_id)) = &self.run else {
+            return Ok(());
+        };
+
+        sqlx::query!("DELETE FROM scrape_run_pages WHERE run_id = $1", run_id)
+            .execute(pool)
+            .await
+            .change_context(ApplicationError::WorkQueueError)?;
+
+        Ok(())
+    }
+}
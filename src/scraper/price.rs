@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// The price of a product at a point in time, in cents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Price {
+    /// The normal shelf price of the product.
+    pub original: i32,
+    /// The price the product is currently sold for, including any specials.
+    pub sale: i32,
+    /// The amount saved by purchasing the product at the sale price.
+    ///
+    /// Equivalent to `original - sale`.
+    pub savings: i32,
+}
+
+impl Price {
+    /// Creates a price from an original, sale and savings price in dollars.
+    #[must_use]
+    pub fn from_dollars(original: f32, sale: f32, savings: f32) -> Self {
+        Self {
+            original: to_cents(original),
+            sale: to_cents(sale),
+            savings: to_cents(savings),
+        }
+    }
+}
+
+/// Converts an amount in dollars to cents.
+#[must_use]
+pub fn to_cents(dollars: f32) -> i32 {
+    #[allow(clippy::cast_possible_truncation)]
+    let cents = (dollars * 100.0).round() as i32;
+    cents
+}
@@ -0,0 +1,38 @@
+use std::fmt;
+
+use super::DriftReport;
+
+/// A page of products that could not be retrieved, even after retrying.
+#[derive(Debug, Clone)]
+pub struct FailedPage {
+    /// The name of the category the page belongs to.
+    pub category: String,
+    /// The page number, as used by the supermarket's API.
+    pub page: i64,
+    /// A description of the error that occurred.
+    pub error: String,
+}
+
+impl fmt::Display for FailedPage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "page {} of '{}': {}",
+            self.page, self.category, self.error
+        )
+    }
+}
+
+/// The products fetched from a store, along with any pages that failed.
+///
+/// Failed pages are skipped so that a single misbehaving category does not
+/// prevent the prices of every other product from being saved.
+#[derive(Debug)]
+pub struct ProductFetch<P> {
+    /// The products that were retrieved.
+    pub products: Vec<P>,
+    /// The pages that could not be retrieved.
+    pub failed_pages: Vec<FailedPage>,
+    /// The parts of the responses the decoders did not recognise.
+    pub drift: DriftReport,
+}
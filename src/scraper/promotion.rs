@@ -0,0 +1,36 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of a [`Promotion`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PromotionKind {
+    /// A discount for buying multiple of the product, e.g., "2 for $5".
+    MultiBuy,
+    /// A discounted price only available to members of the supermarket's
+    /// loyalty program, e.g., Countdown's Onecard.
+    ClubPrice,
+}
+
+impl fmt::Display for PromotionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PromotionKind::MultiBuy => write!(f, "multi_buy"),
+            PromotionKind::ClubPrice => write!(f, "club_price"),
+        }
+    }
+}
+
+/// A promotion which changes the effective price of a product.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Promotion {
+    /// The kind of promotion.
+    pub kind: PromotionKind,
+    /// The amount of the product that must be purchased for the promotion to
+    /// apply.
+    pub quantity: i32,
+    /// The total price, in cents, of purchasing `quantity` of the product.
+    pub price: i32,
+    /// If the promotion is only available to loyalty program members.
+    pub member_only: bool,
+}
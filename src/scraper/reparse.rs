@@ -0,0 +1,47 @@
+use std::path::Path;
+
+use error_stack::Report;
+use sqlx::PgPool;
+
+use crate::{
+    config::StoreSelection,
+    error::ApplicationError,
+    scraper::{read_archive, replay::save_offline_products, Scraper},
+};
+
+/// Re-parses an archive of raw responses previously written by
+/// [`super::run`], saving the rebuilt products and their prices without
+/// contacting the supermarket.
+///
+/// The products are decoded with the current decoders, so prices lost to a
+/// change in the supermarket's responses can be recovered once the decoders
+/// are fixed. Pages which still fail to decode are recorded against the run.
+///
+/// The store is selected as described by [`super::replay`], and prices are
+/// recorded at the time the archive was last written to.
+///
+/// # Errors
+/// - If unable to read the archive, or the scraper cannot re-parse it
+/// - If every page of the archive fails to decode
+/// - If unable to list the stores, or `stores` does not select exactly one store
+/// - If unable to save the store, products or prices, or record the run
+#[tracing::instrument(name = "reparse archive", skip_all, fields(supermarket = %S::SUPERMARKET, path = %path.display()))]
+pub async fn reparse<S: Scraper>(
+    scraper: S,
+    connection: PgPool,
+    path: &Path,
+    stores: &StoreSelection,
+    should_insert: bool,
+) -> Result<(), Report<ApplicationError>> {
+    let responses = read_archive(path)?;
+    tracing::debug!("Loaded {} responses from archive", responses.len());
+
+    let fetch = scraper.parse_archive(&responses)?;
+    tracing::debug!(
+        "Rebuilt {} products, {} pages failed to decode",
+        fetch.products.len(),
+        fetch.failed_pages.len()
+    );
+
+    save_offline_products(&scraper, &connection, path, stores, fetch, should_insert).await
+}
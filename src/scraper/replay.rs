@@ -0,0 +1,123 @@
+use std::{fs, path::Path};
+
+use error_stack::Report;
+use sqlx::PgPool;
+
+use crate::{
+    config::StoreSelection,
+    error::ApplicationError,
+    scraper::{
+        cached_store_key, finish_scrape_run, read_cache, save_failed_pages, save_scraped_products,
+        select_stores, start_scrape_run, DriftReport, PriceStorage, ProductFetch, ScrapeRunOutcome,
+        ScrapedStore, Scraper,
+    },
+};
+
+/// Replays a cache of products previously written by [`super::run`], saving
+/// the products and their prices without contacting the supermarket.
+///
+/// As a cache only contains the products of a single store, `stores` must
+/// select exactly one store. If no stores are selected, the store is found
+/// from the directory the cache was written into. Prices are recorded at the
+/// time the cache was written.
+///
+/// # Errors
+/// - If unable to read or decode the cache
+/// - If unable to list the stores, or `stores` does not select exactly one store
+/// - If unable to save the store, products or prices, or record the run
+#[tracing::instrument(name = "replay cache", skip_all, fields(supermarket = %S::SUPERMARKET, path = %path.display()))]
+pub async fn replay<S: Scraper>(
+    scraper: S,
+    connection: PgPool,
+    path: &Path,
+    stores: &StoreSelection,
+    should_insert: bool,
+) -> Result<(), Report<ApplicationError>> {
+    let products: Vec<S::Product> = read_cache(path)?;
+    tracing::debug!("Loaded {} products from cache", products.len());
+
+    let fetch = ProductFetch {
+        products,
+        failed_pages: Vec::new(),
+        drift: DriftReport::default(),
+    };
+    save_offline_products(&scraper, &connection, path, stores, fetch, should_insert).await
+}
+
+/// Saves products which were retrieved from a file at `path`, rather than
+/// from the supermarket, as a new scrape run.
+///
+/// The store is selected as described by [`replay`], and prices are recorded
+/// at the time `path` was last modified. Every price is inserted, as an older
+/// file cannot be compared against the latest prices.
+///
+/// # Errors
+/// - If unable to list the stores, or `stores` does not select exactly one store
+/// - If unable to save the store, products, prices or failed pages, or record the run
+pub(super) async fn save_offline_products<S: Scraper>(
+    scraper: &S,
+    connection: &PgPool,
+    path: &Path,
+    stores: &StoreSelection,
+    fetch: ProductFetch<S::Product>,
+    should_insert: bool,
+) -> Result<(), Report<ApplicationError>> {
+    // the file was written as soon as the products were scraped
+    let recorded_at = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+
+    let known_stores = scraper.list_stores(connection).await?;
+    let stores = match (stores, cached_store_key(path, &S::SUPERMARKET)) {
+        (StoreSelection::Default, Some(store_key)) => StoreSelection::Only(vec![store_key]),
+        (stores, _) => stores.clone(),
+    };
+    let mut stores = select_stores(known_stores, &stores, S::DEFAULT_STORES)?;
+    let (Some(store), None) = (stores.pop(), stores.pop()) else {
+        return Err(Report::new(ApplicationError::Config).attach_printable(
+            "A cache or archive only contains the products of one store, so exactly one store must be selected with --stores",
+        ));
+    };
+    tracing::debug!("Replaying prices for '{}'", store.name());
+
+    let store_id = scraper.save_store(connection, &store).await?;
+    let run_id = if should_insert {
+        Some(start_scrape_run(connection, &S::SUPERMARKET, store_id, &store.id()).await?)
+    } else {
+        None
+    };
+
+    let result = async {
+        if let (Some(run_id), false) = (run_id, fetch.failed_pages.is_empty()) {
+            save_failed_pages(connection, run_id, &fetch.failed_pages).await?;
+        }
+
+        save_scraped_products(
+            scraper,
+            connection,
+            &fetch.products,
+            store_id,
+            run_id,
+            recorded_at,
+            should_insert,
+            PriceStorage::Every,
+        )
+        .await
+    }
+    .await;
+
+    if let Some(run_id) = run_id {
+        let outcome = match &result {
+            Ok(()) => ScrapeRunOutcome::Completed {
+                product_count: i32::try_from(fetch.products.len()).unwrap_or(i32::MAX),
+                drift: fetch.drift.clone(),
+            },
+            Err(report) => ScrapeRunOutcome::Failed {
+                error: format!("{report:?}"),
+            },
+        };
+        finish_scrape_run(connection, run_id, outcome).await?;
+    }
+
+    result
+}
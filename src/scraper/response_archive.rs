@@ -0,0 +1,177 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use error_stack::{Report, Result, ResultExt};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ApplicationError,
+    scraper::cache::{file_timestamp, sanitize, store_directory},
+    supermarket::Supermarket,
+};
+
+/// The raw body of a response, along with what was requested to receive it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchivedResponse {
+    /// The URL that was requested, including its query.
+    pub url: String,
+    /// The name of the category of products the response contains, if any.
+    pub category: Option<String>,
+    /// The page of products the response contains, if any.
+    pub page: Option<i64>,
+    /// The body of the response, exactly as it was received.
+    pub body: String,
+}
+
+/// A directory that the raw responses of scraping a single store are
+/// archived into, so they can be audited, or re-parsed once the decoders
+/// have been fixed.
+///
+/// Each response is written to its own gzip compressed file, so workers can
+/// archive pages concurrently.
+#[derive(Debug, Clone)]
+pub struct ResponseArchive {
+    /// The directory responses are written into, or `None` if responses are
+    /// not archived.
+    directory: Option<PathBuf>,
+}
+
+impl ResponseArchive {
+    /// An archive which discards every response.
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self { directory: None }
+    }
+
+    /// Creates a new archive for scraping the store, as
+    /// `<root>/<supermarket>/<store>/<timestamp>/`.
+    ///
+    /// # Errors
+    /// If unable to create the directory.
+    pub fn create(
+        root: &Path,
+        supermarket: &Supermarket,
+        store_key: &str,
+    ) -> Result<Self, ApplicationError> {
+        let timestamp = file_timestamp().change_context(ApplicationError::ArchiveError)?;
+        let directory = store_directory(root, supermarket, store_key).join(timestamp);
+        fs::create_dir_all(&directory)
+            .change_context(ApplicationError::ArchiveError)
+            .attach_printable_lazy(|| format!("When creating {}", directory.display()))?;
+
+        Ok(Self {
+            directory: Some(directory),
+        })
+    }
+
+    /// The directory responses are written into, if responses are archived.
+    #[must_use]
+    pub fn directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+
+    /// Archives a response under `name`, which should be unique within the
+    /// archive.
+    ///
+    /// Does nothing if the archive is disabled.
+    ///
+    /// # Errors
+    /// - If a response was already archived under the sanitized `name`, which
+    ///   is never overwritten
+    /// - If unable to write the file
+    pub fn save(&self, name: &str, response: &ArchivedResponse) -> Result<(), ApplicationError> {
+        let Some(directory) = &self.directory else {
+            return Ok(());
+        };
+
+        let path = directory.join(format!("{}.json.gz", sanitize(name)));
+        let file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .change_context(ApplicationError::ArchiveError)
+            .attach_printable_lazy(|| format!("When creating {}", path.display()))?;
+
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+        serde_json::to_writer(&mut encoder, response)
+            .change_context(ApplicationError::ArchiveError)?;
+        encoder
+            .finish()
+            .and_then(|mut writer| writer.flush())
+            .change_context(ApplicationError::ArchiveError)
+            .attach_printable_lazy(|| format!("When writing {}", path.display()))
+    }
+}
+
+/// Reads every response of an archive directory written by a
+/// [`ResponseArchive`], ordered by the name they were archived under.
+///
+/// # Errors
+/// - If unable to read the directory, or any of its files
+/// - If unable to decode an archived response
+/// - If the directory contains no responses
+pub fn read_archive(directory: &Path) -> Result<Vec<ArchivedResponse>, ApplicationError> {
+    let mut paths = fs::read_dir(directory)
+        .change_context(ApplicationError::ArchiveError)
+        .attach_printable_lazy(|| format!("When reading {}", directory.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .change_context(ApplicationError::ArchiveError)?;
+    paths.retain(|path| path.to_string_lossy().ends_with(".json.gz"));
+    paths.sort();
+
+    if paths.is_empty() {
+        return Err(
+            Report::new(ApplicationError::ArchiveError).attach_printable(format!(
+                "{} does not contain any archived responses",
+                directory.display()
+            )),
+        );
+    }
+
+    paths
+        .iter()
+        .map(|path| {
+            let file = File::open(path)
+                .change_context(ApplicationError::ArchiveError)
+                .attach_printable_lazy(|| format!("When reading {}", path.display()))?;
+
+            serde_json::from_reader(GzDecoder::new(BufReader::new(file)))
+                .change_context(ApplicationError::ArchiveError)
+                .attach_printable_lazy(|| format!("When decoding {}", path.display()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A response of the `page` of products of the `category`.
+    fn page(category: &str, page: i64) -> ArchivedResponse {
+        ArchivedResponse {
+            url: format!("https://example.com/products?page={page}"),
+            category: Some(category.to_string()),
+            page: Some(page),
+            body: "{}".to_string(),
+        }
+    }
+
+    #[test]
+    fn never_overwrites_a_response() {
+        let root = tempfile::tempdir().unwrap();
+        let archive = ResponseArchive::create(root.path(), &Supermarket::Countdown, "1").unwrap();
+
+        archive.save("products-A&B-0001", &page("A&B", 1)).unwrap();
+        // sanitized to the same file name as the first response
+        assert!(archive.save("products-A,B-0001", &page("A,B", 1)).is_err());
+
+        let responses = read_archive(archive.directory().unwrap()).unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].category.as_deref(), Some("A&B"));
+    }
+}
@@ -0,0 +1,298 @@
+use error_stack::Report;
+use sqlx::PgPool;
+
+use crate::{
+    config::StoreSelection,
+    error::ApplicationError,
+    scraper::{
+        finish_scrape_run, get_scrape_run, lock_store, resume_scrape_run, save_failed_pages,
+        save_scraped_products, select_stores, start_scrape_run, write_cache, CacheConfig,
+        DriftReport, PageQueue, PriceStorage, ResponseArchive, ScrapeRunOutcome, ScrapedStore,
+        Scraper, Shutdown,
+    },
+};
+
+/// Runs a supermarket scraper across the selected stores.
+///
+/// `should_insert` indicates if the scraper should insert data into the database,
+/// and `price_storage` which prices are inserted.
+/// When inserting, each store is recorded as a scrape run in the database,
+/// along with any pages of products that failed to be retrieved.
+///
+/// The products of each store are cached according to `cache`, which also
+/// describes where raw responses are archived.
+///
+/// If `resume` is the id of an interrupted run, only the store of that run is
+/// scraped, retrieving only the pages that the run had not yet retrieved.
+///
+/// While inserting, each store is locked for the duration of its scrape, so
+/// another run scraping the same store fails rather than recording its prices
+/// twice.
+///
+/// Once `shutdown` is requested, no more stores are scraped, and a store still
+/// retrieving products is left unfinished, so its run can be resumed.
+///
+/// # Errors
+/// - If unable to list the stores, or a selected store is unknown
+/// - If the run to resume cannot be resumed
+/// - If another run is scraping a selected store
+/// - If unable to save a store, or record its scrape run
+/// - If unable to scrape a store
+#[tracing::instrument(name = "run scraper", skip_all, fields(supermarket = %S::SUPERMARKET))]
+#[allow(clippy::too_many_arguments)]
+pub async fn run<S: Scraper>(
+    scraper: S,
+    connection: PgPool,
+    stores: &StoreSelection,
+    should_insert: bool,
+    price_storage: PriceStorage,
+    resume: Option<i32>,
+    cache: &CacheConfig,
+    shutdown: &Shutdown,
+) -> Result<(), Report<ApplicationError>> {
+    let known_stores = scraper.list_stores(&connection).await?;
+    tracing::debug!("Found {} known stores", known_stores.len());
+
+    let stores = match resume {
+        Some(run_id) => {
+            if !should_insert {
+                return Err(Report::new(ApplicationError::Config)
+                    .attach_printable("Resuming a run requires inserting into the database"));
+            }
+
+            let store_key = get_resumable_store_key::<S>(&connection, run_id).await?;
+            tracing::info!("Resuming run {run_id} of store {store_key}");
+            select_stores(
+                known_stores,
+                &StoreSelection::Only(vec![store_key]),
+                S::DEFAULT_STORES,
+            )?
+        }
+        None => select_stores(known_stores, stores, S::DEFAULT_STORES)?,
+    };
+
+    for store in stores {
+        if shutdown.is_requested() {
+            tracing::info!("Shutting down before scraping '{}'", store.name());
+            break;
+        }
+
+        tracing::debug!("Retrieving prices for '{}'", store.name());
+
+        // store the store if it has not been created before
+        let store_id = scraper.save_store(&connection, &store).await?;
+
+        let lock = if should_insert {
+            let Some(lock) = lock_store(&connection, store_id).await? else {
+                return Err(
+                    Report::new(ApplicationError::StoreLockError).attach_printable(format!(
+                        "'{}' is already being scraped by another run",
+                        store.name()
+                    )),
+                );
+            };
+            Some(lock)
+        } else {
+            None
+        };
+
+        let run_id = match resume {
+            Some(run_id) => {
+                resume_scrape_run(&connection, run_id).await?;
+                Some(run_id)
+            }
+            None if should_insert => {
+                Some(start_scrape_run(&connection, &S::SUPERMARKET, store_id, &store.id()).await?)
+            }
+            None => None,
+        };
+
+        let result = run_store(
+            &scraper,
+            &connection,
+            &store,
+            store_id,
+            run_id,
+            should_insert,
+            price_storage,
+            cache,
+            shutdown,
+        )
+        .await;
+
+        let outcome = match &result {
+            // the run is left unfinished, so that it can be resumed
+            Ok(None) => {
+                tracing::warn!("Shut down while scraping '{}'", store.name());
+                if let Some(run_id) = run_id {
+                    tracing::warn!("Resume the run with --resume {run_id}");
+                }
+                break;
+            }
+            Ok(Some(summary)) => ScrapeRunOutcome::Completed {
+                product_count: summary.product_count,
+                drift: summary.drift.clone(),
+            },
+            Err(report) => ScrapeRunOutcome::Failed {
+                error: format!("{report:?}"),
+            },
+        };
+        if let Some(run_id) = run_id {
+            finish_scrape_run(&connection, run_id, outcome).await?;
+        }
+        if let Some(lock) = lock {
+            lock.release().await?;
+        }
+
+        result?;
+    }
+
+    Ok(())
+}
+
+/// Retrieves the id of the store an interrupted run was scraping.
+///
+/// # Errors
+/// If the run does not exist, belongs to another supermarket, has finished
+/// saving prices, or was started before runs could be resumed.
+async fn get_resumable_store_key<S: Scraper>(
+    connection: &PgPool,
+    run_id: i32,
+) -> Result<String, Report<ApplicationError>> {
+    let run = get_scrape_run(connection, run_id).await?;
+
+    let reason = if run.supermarket != S::SUPERMARKET.to_string() {
+        Some(format!("the run is for {}", run.supermarket))
+    } else if run.completed {
+        Some("the run has already completed".to_string())
+    } else if run.has_prices {
+        Some("the run has already saved prices".to_string())
+    } else {
+        None
+    };
+    if let Some(reason) = reason {
+        return Err(Report::new(ApplicationError::ScrapeRunError)
+            .attach_printable(format!("Unable to resume run {run_id}, as {reason}")));
+    }
+
+    run.store_key.ok_or_else(|| {
+        Report::new(ApplicationError::ScrapeRunError).attach_printable(format!(
+            "Unable to resume run {run_id}, as it was started before runs could be resumed"
+        ))
+    })
+}
+
+/// A summary of scraping a single store.
+struct StoreSummary {
+    /// The amount of products that were found.
+    product_count: i32,
+    /// What was not recognised in the supermarket's responses.
+    drift: DriftReport,
+}
+
+/// Scrapes a single store, returning a summary of what was scraped, or `None`
+/// if `shutdown` was requested before all products were retrieved.
+///
+/// Pages of products which failed to be retrieved are logged and recorded
+/// against the run, while the prices of every other product are still saved.
+///
+/// # Errors
+/// - If unable to select the store
+/// - If unable to create the response archive
+/// - If unable to retrieve all categories of products
+/// - If unable to retrieve all products
+/// - If unable to save products, prices or failed pages
+#[allow(clippy::too_many_arguments)]
+async fn run_store<S: Scraper>(
+    scraper: &S,
+    connection: &PgPool,
+    store: &S::Store,
+    store_id: i32,
+    run_id: Option<i32>,
+    should_insert: bool,
+    price_storage: PriceStorage,
+    cache: &CacheConfig,
+    shutdown: &Shutdown,
+) -> Result<Option<StoreSummary>, Report<ApplicationError>> {
+    scraper.select_store(store).await?;
+
+    let archive = cache.archive_directory.as_deref().map_or_else(
+        || Ok(ResponseArchive::disabled()),
+        |directory| ResponseArchive::create(directory, &S::SUPERMARKET, &store.id()),
+    )?;
+    if let Some(directory) = archive.directory() {
+        tracing::debug!("Archiving responses to {}", directory.display());
+    }
+
+    // retrieve categories
+    tracing::debug!("Retrieving all categories...");
+    let categories = scraper.list_categories(store, &archive).await?;
+    tracing::debug!(
+        "Retrieved the following categories: {}",
+        categories
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    // retrieve products from all categories
+    tracing::debug!("Retrieving all products. This may take a while...");
+    let queue = run_id.map_or_else(PageQueue::in_memory, |run_id| {
+        PageQueue::persisted(connection.clone(), run_id)
+    });
+    let Some(fetch) = shutdown
+        .run_until(scraper.fetch_products(store, categories, &queue, &archive))
+        .await
+    else {
+        return Ok(None);
+    };
+    let fetch = fetch?;
+    let products = fetch.products;
+    tracing::debug!("{:?} products were found", products.len());
+
+    if !fetch.failed_pages.is_empty() {
+        tracing::warn!(
+            "Failed to retrieve {} pages of products, their prices will not be saved",
+            fetch.failed_pages.len()
+        );
+        for failed_page in &fetch.failed_pages {
+            tracing::warn!("Failed to retrieve {failed_page}");
+        }
+
+        if let Some(run_id) = run_id {
+            save_failed_pages(connection, run_id, &fetch.failed_pages).await?;
+        }
+    }
+
+    if !fetch.drift.is_empty() {
+        tracing::warn!(
+            "The responses contained items or fields that were not recognised, the API may have changed:\n{}",
+            fetch.drift
+        );
+    }
+
+    // cache the result
+    let cache_path = write_cache(cache, &S::SUPERMARKET, &store.id(), &products)?;
+    tracing::debug!("Cached products to {}", cache_path.display());
+
+    save_scraped_products(
+        scraper,
+        connection,
+        &products,
+        store_id,
+        run_id,
+        None,
+        should_insert,
+        price_storage,
+    )
+    .await?;
+
+    // the run has finished, so it no longer needs to be resumed
+    queue.clear().await?;
+
+    Ok(Some(StoreSummary {
+        product_count: i32::try_from(products.len()).unwrap_or(i32::MAX),
+        drift: fetch.drift,
+    }))
+}
@@ -0,0 +1,91 @@
+use std::collections::{BTreeSet, HashMap};
+
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::{error::ApplicationError, supermarket::Supermarket};
+
+use super::ScrapedProduct;
+
+/// Saves the categories of products, and which categories each product
+/// belongs to, into the database.
+///
+/// `stored_product_ids` maps each product's [`ScrapedProduct::key`] to its id
+/// in the `products` table, as returned by
+/// [`super::Scraper::get_product_ids`].
+#[tracing::instrument(name = "save categories", level = "debug", skip_all, fields(
+	product_count = %products.len()
+))]
+#[allow(clippy::implicit_hasher)]
+pub async fn save_categories<P: ScrapedProduct>(
+    pool: &PgPool,
+    supermarket: &Supermarket,
+    products: &[P],
+    stored_product_ids: &HashMap<String, i32>,
+) -> Result<(), ApplicationError> {
+    let supermarket = supermarket.to_string();
+    let category_names = products
+        .iter()
+        .flat_map(ScrapedProduct::categories)
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    // insert any new categories, then retrieve the ids of all categories
+    sqlx::query!(
+        r"INSERT INTO categories (supermarket, name)
+			SELECT $1::text::supermarket, UNNEST($2::text[])
+			ON CONFLICT (supermarket, name) DO NOTHING",
+        supermarket,
+        &category_names[..]
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::CategoryInsertionError)?;
+
+    let category_ids = sqlx::query!(
+        r"SELECT id, name FROM categories WHERE supermarket = $1::text::supermarket",
+        supermarket
+    )
+    .fetch_all(pool)
+    .await
+    .change_context(ApplicationError::CategoryInsertionError)?
+    .into_iter()
+    .map(|category| (category.name, category.id))
+    .collect::<HashMap<_, _>>();
+
+    let mut product_ids = Vec::new();
+    let mut product_category_ids = Vec::new();
+    for product in products {
+        let Some(product_id) = stored_product_ids.get(product.key()) else {
+            continue;
+        };
+
+        for category in product.categories() {
+            if let Some(category_id) = category_ids.get(category) {
+                product_ids.push(*product_id);
+                product_category_ids.push(*category_id);
+            }
+        }
+    }
+
+    let new_product_categories = sqlx::query!(
+        r"INSERT INTO product_categories (product_id, category_id)
+			SELECT * FROM UNNEST($1::integer[], $2::integer[])
+			ON CONFLICT DO NOTHING",
+        &product_ids[..],
+        &product_category_ids[..]
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::CategoryInsertionError)?;
+
+    debug!(
+        "{} new product categories",
+        new_product_categories.rows_affected()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,261 @@
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+use tracing::{debug, warn};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::error::ApplicationError;
+
+use super::{save_promotions, Promotion, ScrapedProduct};
+
+/// Which of the prices of a scrape are inserted into the `prices` table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceStorage {
+    /// Every price, whether or not it changed since it was last seen.
+    #[default]
+    Every,
+    /// Only prices which differ from the last price of the product at the
+    /// store, or whose promotions differ. When each price was last seen is
+    /// kept in `latest_prices`.
+    Changes,
+}
+
+/// Bulk saves the prices of `products` to a Postgres database.
+///
+/// `stored_product_ids` maps each product's [`ScrapedProduct::key`] to its id
+/// in the `products` table, as returned by
+/// [`super::Scraper::get_product_ids`].
+///
+/// The prices are recorded at `recorded_at`, or the current time if `None`.
+/// Every product is marked as last seen at that time, while `price_storage`
+/// decides if prices which have not changed are inserted again.
+#[tracing::instrument(
+	name = "save prices",
+	level = "debug",
+	skip_all,
+	fields(
+		product_count = %products.len(),
+		skip_insert = %!should_insert,
+		%store_id,
+		?run_id,
+		?price_storage
+	)
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn save_prices<P: ScrapedProduct>(
+    pool: &PgPool,
+    products: &[P],
+    mut stored_product_ids: HashMap<String, i32>,
+    store_id: i32,
+    run_id: Option<i32>,
+    recorded_at: Option<SystemTime>,
+    should_insert: bool,
+    price_storage: PriceStorage,
+) -> Result<(), ApplicationError> {
+    // We perform the bulk save by first retrieving all the product IDs in
+    // the Postgres database that are under the supermarket being scraped.
+    // In the future, we might have to look at optimizing this in some other
+    // way, to ensure that we are only getting the product IDs of items inside
+    // `products`. Over-fetching is okay here, as it gives us considerably
+    // less queries to perform.
+    // Once we have all the products for the supermarket, we map from our API
+    // fetched products to Postgres products by matching their keys.
+    let mut product_ids = Vec::with_capacity(products.len());
+    let mut cost_in_cents = Vec::with_capacity(products.len());
+    let mut original_cost_in_cents = Vec::with_capacity(products.len());
+    let mut savings_in_cents = Vec::with_capacity(products.len());
+    let mut unit_price_in_cents = Vec::with_capacity(products.len());
+    let mut promotion_keys = Vec::with_capacity(products.len());
+    let mut promotions = HashMap::new();
+
+    for product in products {
+        // find the corresponding stored product
+        let Some(id) = stored_product_ids.remove(product.key()) else {
+            warn!(
+                "Failed to get stored product ID for key '{}' ('{}')",
+                product.key(),
+                product.name()
+            );
+            continue;
+        };
+
+        product_ids.push(id);
+        let price = product.price();
+        cost_in_cents.push(price.sale);
+        original_cost_in_cents.push(price.original);
+        savings_in_cents.push(price.savings);
+        unit_price_in_cents.push(product.unit_price());
+        promotion_keys.push(promotion_key(product.promotions()));
+        if !product.promotions().is_empty() {
+            promotions.insert(id, product.promotions());
+        }
+    }
+
+    if !stored_product_ids.is_empty() {
+        warn!(
+				"Failed to find {} products inserted in database. This may be the case if the `--no-insert` flag was run",
+				stored_product_ids.len()
+			);
+        if should_insert {
+            warn!(
+                "Products not found in database: {:?}",
+                stored_product_ids.keys()
+            );
+        }
+    }
+
+    // times before the epoch are not meaningful for a scrape, so treat them as now
+    let recorded_at = recorded_at
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|time| time.as_secs_f64());
+
+    if should_insert {
+        // Insert the prices, or only those which changed since the product was
+        // last seen at the store, then record that every product was seen.
+        // Every CTE sees `latest_prices` as it was before the statement.
+        let price_ids = sqlx::query!(
+            r#"WITH scraped AS (
+				SELECT * FROM UNNEST(
+					$1::integer[],
+					$2::integer[],
+					$3::integer[],
+					$4::integer[],
+					$5::integer[],
+					$10::text[]
+				) AS scraped (
+					product_id,
+					cost_in_cents,
+					original_cost_in_cents,
+					savings_in_cents,
+					unit_price_in_cents,
+					promotions
+				)
+			), recorded AS (
+				SELECT COALESCE(TO_TIMESTAMP($8::float8), NOW()) AS time
+			), latest_promotions AS (
+				-- keyed as by `promotion_key`
+				SELECT price_id, STRING_AGG(promotion, ';' ORDER BY promotion COLLATE "C") AS promotions
+				FROM (
+					SELECT price_id,
+						promotion_type || ':' || quantity || ':' || price_in_cents || ':' || member_only
+							AS promotion
+					FROM promotions
+					WHERE price_id IN (SELECT price_id FROM latest_prices WHERE store_id = $6)
+				) AS promotions
+				GROUP BY price_id
+			), latest AS (
+				SELECT scraped.product_id, latest_prices.price_id, prices.cost_in_cents,
+					prices.original_cost_in_cents, prices.savings_in_cents,
+					prices.unit_price_in_cents,
+					COALESCE(latest_promotions.promotions, '') AS promotions
+				FROM scraped
+				INNER JOIN latest_prices
+					ON latest_prices.product_id = scraped.product_id
+					AND latest_prices.store_id = $6
+				INNER JOIN prices ON prices.id = latest_prices.price_id
+				LEFT JOIN latest_promotions ON latest_promotions.price_id = latest_prices.price_id
+			), inserted AS (
+				INSERT INTO prices (
+					product_id,
+					cost_in_cents,
+					original_cost_in_cents,
+					savings_in_cents,
+					unit_price_in_cents,
+					store_id,
+					run_id,
+					time
+				) SELECT
+					scraped.product_id,
+					scraped.cost_in_cents,
+					scraped.original_cost_in_cents,
+					scraped.savings_in_cents,
+					scraped.unit_price_in_cents,
+					$6,
+					$7,
+					recorded.time
+				FROM scraped
+				CROSS JOIN recorded
+				LEFT JOIN latest ON latest.product_id = scraped.product_id
+				WHERE NOT $9::boolean
+					OR latest.price_id IS NULL
+					OR latest.cost_in_cents IS DISTINCT FROM scraped.cost_in_cents
+					OR latest.original_cost_in_cents IS DISTINCT FROM scraped.original_cost_in_cents
+					OR latest.savings_in_cents IS DISTINCT FROM scraped.savings_in_cents
+					OR latest.unit_price_in_cents IS DISTINCT FROM scraped.unit_price_in_cents
+					OR latest.promotions IS DISTINCT FROM scraped.promotions
+				RETURNING id, product_id
+			), seen AS (
+				INSERT INTO latest_prices (product_id, store_id, price_id, last_seen, last_run_id)
+				SELECT
+					scraped.product_id,
+					$6,
+					COALESCE(inserted.id, latest.price_id),
+					recorded.time,
+					$7
+				FROM scraped
+				CROSS JOIN recorded
+				LEFT JOIN inserted ON inserted.product_id = scraped.product_id
+				LEFT JOIN latest ON latest.product_id = scraped.product_id
+				ON CONFLICT (product_id, store_id) DO UPDATE SET
+					price_id = EXCLUDED.price_id,
+					last_seen = EXCLUDED.last_seen,
+					last_run_id = EXCLUDED.last_run_id
+				-- an older cache being replayed must not replace a newer price
+				WHERE latest_prices.last_seen <= EXCLUDED.last_seen
+			)
+			SELECT id AS "id!", product_id AS "product_id!" FROM inserted"#,
+            &product_ids[..],
+            &cost_in_cents[..],
+            &original_cost_in_cents[..],
+            &savings_in_cents[..],
+            &unit_price_in_cents[..] as &[Option<i32>],
+            store_id,
+            run_id,
+            recorded_at,
+            price_storage == PriceStorage::Changes,
+            &promotion_keys[..]
+        )
+        .fetch_all(pool)
+        .await
+        .change_context(ApplicationError::PriceDataInsertionError)?
+        .into_iter()
+        .map(|price| (price.product_id, price.id))
+        .collect::<HashMap<_, _>>();
+
+        debug!(
+            "Inserted {} prices, {} were unchanged",
+            price_ids.len(),
+            product_ids.len() - price_ids.len()
+        );
+
+        save_promotions(pool, &price_ids, &promotions).await?;
+    } else {
+        debug!("Skipped inserting prices into database");
+    }
+
+    Ok(())
+}
+
+/// Describes a set of promotions as text, so promotions which changed since a
+/// price was last seen can be found, regardless of their order.
+fn promotion_key(promotions: &[Promotion]) -> String {
+    let mut promotions: Vec<_> = promotions
+        .iter()
+        .map(|promotion| {
+            format!(
+                "{}:{}:{}:{}",
+                promotion.kind, promotion.quantity, promotion.price, promotion.member_only
+            )
+        })
+        .collect();
+    promotions.sort_unstable();
+
+    promotions.join(";")
+}
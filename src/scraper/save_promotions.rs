@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+use tracing::debug;
+
+use crate::error::ApplicationError;
+
+use super::Promotion;
+
+/// Bulk saves the promotions of products against their price observations.
+///
+/// `price_ids` maps the id of each product in the `products` table to the id
+/// of its newly inserted row in the `prices` table, while `promotions` maps
+/// the id of each product to its promotions.
+#[tracing::instrument(name = "save promotions", level = "debug", skip_all)]
+#[allow(clippy::implicit_hasher)]
+pub async fn save_promotions(
+    pool: &PgPool,
+    price_ids: &HashMap<i32, i32>,
+    promotions: &HashMap<i32, &[Promotion]>,
+) -> Result<(), ApplicationError> {
+    let mut promotion_price_ids = Vec::new();
+    let mut promotion_types = Vec::new();
+    let mut quantities = Vec::new();
+    let mut price_in_cents = Vec::new();
+    let mut member_only = Vec::new();
+
+    for (product_id, product_promotions) in promotions {
+        let Some(price_id) = price_ids.get(product_id) else {
+            continue;
+        };
+
+        for promotion in *product_promotions {
+            promotion_price_ids.push(*price_id);
+            promotion_types.push(promotion.kind.to_string());
+            quantities.push(promotion.quantity);
+            price_in_cents.push(promotion.price);
+            member_only.push(promotion.member_only);
+        }
+    }
+
+    sqlx::query!(
+        "INSERT INTO promotions (
+			price_id,
+			promotion_type,
+			quantity,
+			price_in_cents,
+			member_only
+		) SELECT * FROM UNNEST(
+			$1::integer[],
+			$2::text[],
+			$3::integer[],
+			$4::integer[],
+			$5::boolean[]
+		)",
+        &promotion_price_ids[..],
+        &promotion_types[..],
+        &quantities[..],
+        &price_in_cents[..],
+        &member_only[..]
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::PriceDataInsertionError)?;
+
+    debug!("Inserted {} promotions", promotion_price_ids.len());
+
+    Ok(())
+}
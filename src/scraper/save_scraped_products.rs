@@ -0,0 +1,89 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::SystemTime,
+};
+
+use error_stack::Result;
+use sqlx::PgPool;
+
+use crate::error::ApplicationError;
+
+use super::{
+    get_off_sale_products, get_stocked_product_ids, save_categories, save_prices, PriceStorage,
+    ScrapedProduct, Scraper,
+};
+
+/// Saves products scraped from a store, along with their categories and
+/// prices, logging how many previously known products are now off-sale.
+///
+/// The prices are recorded at `recorded_at`, or the current time if `None`,
+/// and stored as described by `price_storage`.
+///
+/// # Errors
+/// - If unable to save the products or their categories
+/// - If unable to retrieve the ids of the stored products
+/// - If unable to save the prices
+#[tracing::instrument(
+    name = "save scraped products",
+    level = "debug",
+    skip_all,
+    fields(product_count = %products.len(), %store_id)
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn save_scraped_products<S: Scraper>(
+    scraper: &S,
+    connection: &PgPool,
+    products: &[S::Product],
+    store_id: i32,
+    run_id: Option<i32>,
+    recorded_at: Option<SystemTime>,
+    should_insert: bool,
+    price_storage: PriceStorage,
+) -> Result<(), ApplicationError> {
+    if should_insert {
+        // create the products if not existing before
+        scraper.save_products(connection, products).await?;
+    }
+
+    let stored_product_ids = scraper.get_product_ids(connection).await?;
+
+    // products can be shared with other supermarkets, so only those priced at
+    // one of its stores before, or scraped now, are products of the supermarket
+    let stocked_product_ids = get_stocked_product_ids(connection, &S::SUPERMARKET).await?;
+    let scraped_keys = products
+        .iter()
+        .map(ScrapedProduct::key)
+        .collect::<HashSet<_>>();
+    let stored_product_ids = stored_product_ids
+        .into_iter()
+        .filter(|(key, id)| stocked_product_ids.contains(id) || scraped_keys.contains(key.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    if should_insert {
+        // record which categories each product belongs to
+        save_categories(connection, &S::SUPERMARKET, products, &stored_product_ids).await?;
+    }
+
+    // log how many items are now off-sale
+    // products on failed pages will also appear to be off-sale
+    let off_sale_products = get_off_sale_products(&stored_product_ids, products);
+    if !off_sale_products.is_empty() {
+        tracing::debug!(
+            "Failed to find {} previously known products. These items are likely now off-sale",
+            off_sale_products.len()
+        );
+    }
+
+    // upload all price data
+    save_prices(
+        connection,
+        products,
+        stored_product_ids,
+        store_id,
+        run_id,
+        recorded_at,
+        should_insert,
+        price_storage,
+    )
+    .await
+}
@@ -0,0 +1,199 @@
+use error_stack::{Result, ResultExt};
+use sqlx::PgPool;
+
+use crate::{error::ApplicationError, supermarket::Supermarket, GIT_VERSION};
+
+use super::{DriftReport, FailedPage};
+
+/// Records the start of a scrape run against a store, returning the id of the
+/// run.
+///
+/// # Errors
+/// If unable to insert the run into the database.
+#[tracing::instrument(name = "start scrape run", level = "debug", skip_all, fields(%store_id))]
+pub async fn start_scrape_run(
+    pool: &PgPool,
+    supermarket: &Supermarket,
+    store_id: i32,
+    store_key: &str,
+) -> Result<i32, ApplicationError> {
+    let run = sqlx::query!(
+        r"INSERT INTO scrape_runs (supermarket, store_id, store_key, version)
+			VALUES ($1::text::supermarket, $2, $3, $4)
+			RETURNING id",
+        supermarket.to_string(),
+        store_id,
+        store_key,
+        GIT_VERSION
+    )
+    .fetch_one(pool)
+    .await
+    .change_context(ApplicationError::ScrapeRunError)?;
+
+    Ok(run.id)
+}
+
+/// A scrape run previously recorded in the database.
+pub struct ScrapeRun {
+    pub id: i32,
+    /// The name of the supermarket, e.g., `Countdown`.
+    pub supermarket: String,
+    /// The id the supermarket uses for the store, if recorded.
+    pub store_key: Option<String>,
+    pub completed: bool,
+    /// If any prices have been saved by the run.
+    pub has_prices: bool,
+}
+
+/// Retrieves a previously recorded scrape run.
+///
+/// # Errors
+/// - If unable to query the database
+/// - If no run exists with the id
+#[tracing::instrument(name = "get scrape run", level = "debug", skip_all, fields(%run_id))]
+pub async fn get_scrape_run(pool: &PgPool, run_id: i32) -> Result<ScrapeRun, ApplicationError> {
+    sqlx::query_as!(
+        ScrapeRun,
+        r#"SELECT
+			id,
+			supermarket::text AS "supermarket!",
+			store_key,
+			completed,
+			EXISTS (SELECT 1 FROM prices WHERE run_id = scrape_runs.id) AS "has_prices!"
+		FROM scrape_runs
+		WHERE id = $1"#,
+        run_id
+    )
+    .fetch_optional(pool)
+    .await
+    .change_context(ApplicationError::ScrapeRunError)?
+    .ok_or(ApplicationError::ScrapeRunError)
+    .attach_printable_lazy(|| format!("No scrape run exists with id {run_id}"))
+}
+
+/// Records that a scrape run has been resumed, clearing the result of its
+/// previous attempt.
+///
+/// The pages that failed before are still pending, so their failures are
+/// removed, to be recorded again if they fail once more.
+///
+/// # Errors
+/// If unable to update the run in the database.
+#[tracing::instrument(name = "resume scrape run", level = "debug", skip_all, fields(%run_id))]
+pub async fn resume_scrape_run(pool: &PgPool, run_id: i32) -> Result<(), ApplicationError> {
+    sqlx::query!(
+        "UPDATE scrape_runs SET finished_at = NULL, error = NULL WHERE id = $1",
+        run_id
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::ScrapeRunError)?;
+
+    sqlx::query!("DELETE FROM scrape_run_failures WHERE run_id = $1", run_id)
+        .execute(pool)
+        .await
+        .change_context(ApplicationError::ScrapeRunError)?;
+
+    Ok(())
+}
+
+/// The outcome of a scrape run.
+pub enum ScrapeRunOutcome {
+    /// The run saved the prices of `product_count` products. `drift`
+    /// describes what the run did not recognise in the supermarket's
+    /// responses.
+    Completed {
+        product_count: i32,
+        drift: DriftReport,
+    },
+    /// The run stopped early because of an error.
+    Failed { error: String },
+}
+
+/// Records the end of a scrape run.
+///
+/// The errors of the run are the pages recorded by [`save_failed_pages`], and
+/// the error that ended the run, if it failed.
+///
+/// # Errors
+/// - If unable to encode the drift report
+/// - If unable to update the run in the database
+#[tracing::instrument(name = "finish scrape run", level = "debug", skip_all, fields(%run_id))]
+pub async fn finish_scrape_run(
+    pool: &PgPool,
+    run_id: i32,
+    outcome: ScrapeRunOutcome,
+) -> Result<(), ApplicationError> {
+    let (completed, product_count, error_count, error, drift) = match outcome {
+        ScrapeRunOutcome::Completed {
+            product_count,
+            drift,
+        } => {
+            let drift = (!drift.is_empty())
+                .then(|| serde_json::to_string(&drift))
+                .transpose()
+                .change_context(ApplicationError::ScrapeRunError)?;
+            (true, Some(product_count), 0, None, drift)
+        }
+        ScrapeRunOutcome::Failed { error } => (false, None, 1, Some(error), None),
+    };
+
+    sqlx::query!(
+        r"UPDATE scrape_runs SET
+			finished_at = NOW(),
+			completed = $2,
+			product_count = $3,
+			error_count = (
+				SELECT COUNT(*) FROM scrape_run_failures WHERE run_id = $1
+			)::integer + $4,
+			error = $5,
+			drift = $6::text::jsonb
+		WHERE id = $1",
+        run_id,
+        completed,
+        product_count,
+        error_count,
+        error,
+        drift
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::ScrapeRunError)?;
+
+    Ok(())
+}
+
+/// Records the pages of products a scrape run failed to retrieve.
+///
+/// # Errors
+/// If unable to insert the failures into the database.
+#[tracing::instrument(
+    name = "save failed pages",
+    level = "debug",
+    skip_all,
+    fields(%run_id, count = failed_pages.len())
+)]
+pub async fn save_failed_pages(
+    pool: &PgPool,
+    run_id: i32,
+    failed_pages: &[FailedPage],
+) -> Result<(), ApplicationError> {
+    let (categories, pages, errors): (Vec<_>, Vec<_>, Vec<_>) = failed_pages
+        .iter()
+        .map(|failed| (failed.category.clone(), failed.page, failed.error.clone()))
+        .collect();
+
+    sqlx::query!(
+        r"INSERT INTO scrape_run_failures (run_id, category, page, error)
+			SELECT $1, * FROM UNNEST($2::text[], $3::bigint[], $4::text[])",
+        run_id,
+        &categories,
+        &pages,
+        &errors
+    )
+    .execute(pool)
+    .await
+    .change_context(ApplicationError::ScrapeRunError)?;
+
+    Ok(())
+}
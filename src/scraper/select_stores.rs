@@ -0,0 +1,49 @@
+use error_stack::{Result, ResultExt};
+
+use crate::{config::StoreSelection, error::ApplicationError};
+
+use super::ScrapedStore;
+
+/// Selects the stores to scrape out of every known store.
+///
+/// A store is matched by its id, or case-insensitively by its name.
+///
+/// # Errors
+/// If a requested store is not a known store.
+pub fn select_stores<S: ScrapedStore>(
+    stores: Vec<S>,
+    selection: &StoreSelection,
+    default_stores: &[&str],
+) -> Result<Vec<S>, ApplicationError> {
+    let requested = match selection {
+        StoreSelection::All => return Ok(stores),
+        StoreSelection::Default => default_stores
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        StoreSelection::Only(requested) => requested.clone(),
+    };
+
+    let position = |store_name: &str| {
+        stores.iter().position(|store| {
+            store.id() == store_name || store.name().eq_ignore_ascii_case(store_name)
+        })
+    };
+
+    // ensure every store is known before we start scraping
+    let mut indices = Vec::with_capacity(requested.len());
+    for store_name in &requested {
+        let index = position(store_name)
+            .ok_or(ApplicationError::StoreRetrieval)
+            .attach_printable_lazy(|| format!("Failed to find store '{store_name}'"))?;
+        if !indices.contains(&index) {
+            indices.push(index);
+        }
+    }
+
+    let mut stores = stores.into_iter().map(Some).collect::<Vec<_>>();
+    Ok(indices
+        .into_iter()
+        .filter_map(|index| stores[index].take())
+        .collect())
+}
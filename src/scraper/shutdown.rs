@@ -0,0 +1,77 @@
+use std::future::Future;
+
+use tokio::sync::watch;
+
+/// Signals scrapes to stop at the next point they can safely stop at.
+///
+/// Stores are not started once a shutdown is requested, and a store still
+/// retrieving products stops retrieving them, leaving its run to be resumed.
+/// A store which has retrieved its products finishes saving them.
+#[derive(Clone)]
+pub struct Shutdown {
+    /// Receives if a shutdown has been requested, or `None` if a shutdown can
+    /// never be requested.
+    receiver: Option<watch::Receiver<bool>>,
+}
+
+/// Requests the [`Shutdown`] it was created with.
+pub struct ShutdownTrigger {
+    sender: watch::Sender<bool>,
+}
+
+impl Shutdown {
+    /// A shutdown which is never requested.
+    #[must_use]
+    pub fn never() -> Self {
+        Self { receiver: None }
+    }
+
+    /// A shutdown which is requested by the returned trigger.
+    #[must_use]
+    pub fn new() -> (ShutdownTrigger, Self) {
+        let (sender, receiver) = watch::channel(false);
+
+        (
+            ShutdownTrigger { sender },
+            Self {
+                receiver: Some(receiver),
+            },
+        )
+    }
+
+    /// If a shutdown has been requested.
+    #[must_use]
+    pub fn is_requested(&self) -> bool {
+        self.receiver
+            .as_ref()
+            .is_some_and(|receiver| *receiver.borrow())
+    }
+
+    /// Waits until a shutdown is requested, which may be never.
+    pub async fn requested(&self) {
+        let Some(mut receiver) = self.receiver.clone() else {
+            return std::future::pending().await;
+        };
+
+        // if the trigger was dropped, a shutdown can no longer be requested
+        if receiver.wait_for(|requested| *requested).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+
+    /// Runs the `future` until it completes, or a shutdown is requested, in
+    /// which case the future is dropped and `None` is returned.
+    pub async fn run_until<F: Future>(&self, future: F) -> Option<F::Output> {
+        tokio::select! {
+            output = future => Some(output),
+            () = self.requested() => None,
+        }
+    }
+}
+
+impl ShutdownTrigger {
+    /// Requests the shutdown.
+    pub fn request(&self) {
+        self.sender.send_replace(true);
+    }
+}
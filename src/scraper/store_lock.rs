@@ -0,0 +1,59 @@
+use error_stack::{Result, ResultExt};
+use sqlx::{Connection, PgConnection, PgPool};
+
+use crate::error::ApplicationError;
+
+/// The first key of the advisory locks taken on stores, which distinguishes
+/// them from any other advisory lock taken on the database.
+const STORE_LOCK_CLASS: i32 = 0x5354_4f52;
+
+/// An advisory lock on a store, held while it is scraped.
+///
+/// The lock belongs to a connection which is detached from the pool, so it is
+/// released whenever the lock is released or dropped, as the connection is
+/// closed, even if the scrape fails part way.
+pub struct StoreLock {
+    connection: PgConnection,
+}
+
+/// Takes the advisory lock of a store, returning `None` if another run holds
+/// it.
+///
+/// # Errors
+/// If unable to connect to or query the database.
+#[tracing::instrument(name = "lock store", level = "debug", skip_all, fields(%store_id))]
+pub async fn lock_store(
+    pool: &PgPool,
+    store_id: i32,
+) -> Result<Option<StoreLock>, ApplicationError> {
+    let mut connection = pool
+        .acquire()
+        .await
+        .change_context(ApplicationError::StoreLockError)?
+        .detach();
+
+    let locked = sqlx::query_scalar!(
+        r#"SELECT pg_try_advisory_lock($1, $2) AS "locked!""#,
+        STORE_LOCK_CLASS,
+        store_id
+    )
+    .fetch_one(&mut connection)
+    .await
+    .change_context(ApplicationError::StoreLockError)?;
+
+    Ok(locked.then_some(StoreLock { connection }))
+}
+
+impl StoreLock {
+    /// Releases the lock, by closing its connection.
+    ///
+    /// # Errors
+    /// If unable to close the connection cleanly, in which case the lock is
+    /// still released once Postgres notices the connection has closed.
+    pub async fn release(self) -> Result<(), ApplicationError> {
+        self.connection
+            .close()
+            .await
+            .change_context(ApplicationError::StoreLockError)
+    }
+}
@@ -7,15 +7,17 @@ use tracing_subscriber::{
 };
 
 /// Composes multiple layers into a `tracing`'s subscriber.
-pub fn get_tracing_subscriber<Sink>(sink: Sink) -> impl Subscriber
+///
+/// Logs are filtered by the `RUST_LOG` environment variable, falling back to
+/// the `default_filter`.
+pub fn get_tracing_subscriber<Sink>(sink: Sink, default_filter: &str) -> impl Subscriber
 where
     // we use Higher-Rank Trait Bounds (HRTBs) https://doc.rust-lang.org/nomicon/hrtb.html
     // here because we need to Sink to implement `MakeWriter` trait for all choices
     // of our lifetime parameter `'a`
     Sink: for<'a> MakeWriter<'a> + Send + Sync + 'static,
 {
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| "info,supermarket_tracker=debug".into());
+    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| default_filter.into());
     let formatting_layer = tracing_subscriber::fmt::layer()
         .with_writer(sink)
         .with_target(true)
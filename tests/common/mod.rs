@@ -0,0 +1,192 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use wiremock::{
+    matchers::{body_json, method, path, query_param},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// The directory the responses served by [`MockCountdown`] are read from.
+fn fixtures_directory() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/countdown")
+}
+
+/// A local stand-in of the Countdown API, serving the responses in
+/// `tests/fixtures/countdown`.
+///
+/// - `shell.json` is served from `/shell`
+/// - `products/<category>/<page>.json` is served from `/products`, when
+///   browsing the page of the category with the url ending in `<category>`
+/// - Setting the fulfilment method and pickup location always succeeds
+pub struct MockCountdown {
+    pub server: MockServer,
+}
+
+impl MockCountdown {
+    /// Starts the server, mounting every fixture.
+    pub async fn start() -> Self {
+        let server = MockServer::start().await;
+        let fixtures = fixtures_directory();
+
+        Mock::given(method("PUT"))
+            .and(path("/fulfilment/my/methods/pickup"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/fulfilment/my/pickup-addresses"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/shell"))
+            .respond_with(json_fixture(&fixtures.join("shell.json")))
+            .mount(&server)
+            .await;
+
+        for category in fs::read_dir(fixtures.join("products")).unwrap() {
+            let category = category.unwrap().path();
+            let category_url = category.file_name().unwrap().to_str().unwrap().to_string();
+
+            for page in fs::read_dir(&category).unwrap() {
+                let page = page.unwrap().path();
+                let page_number = page.file_stem().unwrap().to_str().unwrap().to_string();
+
+                Mock::given(method("GET"))
+                    .and(path("/products"))
+                    .and(query_param("target", "browse"))
+                    .and(query_param("page", page_number))
+                    .and(query_param(
+                        "dasFilter",
+                        format!("Department;;{category_url};false"),
+                    ))
+                    .respond_with(json_fixture(&page))
+                    .mount(&server)
+                    .await;
+            }
+        }
+
+        Self { server }
+    }
+
+    /// The url of the API, to use as the scraper's base url.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Responds to requests for a page of a category with `status`, rather
+    /// than its fixture.
+    pub async fn fail_page(&self, category_url: &str, page: i64, status: u16) {
+        Mock::given(method("GET"))
+            .and(path("/products"))
+            .and(query_param("page", page.to_string()))
+            .and(query_param(
+                "dasFilter",
+                format!("Department;;{category_url};false"),
+            ))
+            .respond_with(ResponseTemplate::new(status))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Responds to the first request for a page of a category with `status`,
+    /// and to any later request with its fixture.
+    pub async fn fail_page_once(&self, category_url: &str, page: i64, status: u16) {
+        Mock::given(method("GET"))
+            .and(path("/products"))
+            .and(query_param("page", page.to_string()))
+            .and(query_param(
+                "dasFilter",
+                format!("Department;;{category_url};false"),
+            ))
+            .respond_with(ResponseTemplate::new(status))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Responds to requests for a page of a category with the fixture at
+    /// `fixture`, relative to `tests/fixtures/countdown`.
+    pub async fn serve_page(&self, category_url: &str, page: i64, fixture: &str) {
+        Mock::given(method("GET"))
+            .and(path("/products"))
+            .and(query_param("page", page.to_string()))
+            .and(query_param(
+                "dasFilter",
+                format!("Department;;{category_url};false"),
+            ))
+            .respond_with(json_fixture(&fixtures_directory().join(fixture)))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Responds to requests for a page of a category with its fixture, only
+    /// after `delay`.
+    pub async fn delay_page(&self, category_url: &str, page: i64, delay: Duration) {
+        let fixture = fixtures_directory()
+            .join("products")
+            .join(category_url)
+            .join(format!("{page}.json"));
+
+        Mock::given(method("GET"))
+            .and(path("/products"))
+            .and(query_param("page", page.to_string()))
+            .and(query_param(
+                "dasFilter",
+                format!("Department;;{category_url};false"),
+            ))
+            .respond_with(json_fixture(&fixture).set_delay(delay))
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Expects a page of a category to be requested exactly `times` times,
+    /// responding with its fixture.
+    pub async fn expect_page(&self, category_url: &str, page: i64, times: u64) {
+        let fixture = fixtures_directory()
+            .join("products")
+            .join(category_url)
+            .join(format!("{page}.json"));
+
+        Mock::given(method("GET"))
+            .and(path("/products"))
+            .and(query_param("page", page.to_string()))
+            .and(query_param(
+                "dasFilter",
+                format!("Department;;{category_url};false"),
+            ))
+            .respond_with(json_fixture(&fixture))
+            .with_priority(1)
+            .expect(times)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Expects the pickup location to be set to the store exactly once.
+    pub async fn expect_pickup_location(&self, store_id: i32) {
+        Mock::given(method("PUT"))
+            .and(path("/fulfilment/my/pickup-addresses"))
+            .and(body_json(serde_json::json!({ "addressId": store_id })))
+            .respond_with(ResponseTemplate::new(200))
+            .with_priority(1)
+            .expect(1)
+            .mount(&self.server)
+            .await;
+    }
+}
+
+/// Responds with the contents of a JSON fixture.
+fn json_fixture(path: &Path) -> ResponseTemplate {
+    let body = fs::read_to_string(path)
+        .unwrap_or_else(|error| panic!("Failed to read {}: {error}", path.display()));
+
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
@@ -0,0 +1,526 @@
+mod common;
+
+use std::{fs, time::Duration};
+
+use sqlx::PgPool;
+use supermarket_tracker::{
+    config::StoreSelection,
+    countdown::CountdownScraper,
+    rate_limiter::RateLimitConfig,
+    retry::RetryConfig,
+    scraper::{self, CacheConfig, PriceStorage, Shutdown},
+    supermarket::Supermarket,
+};
+use tempfile::TempDir;
+
+use common::MockCountdown;
+
+/// Countdown Mt Eden, which is always known.
+const STORE_ID: i32 = 1_906_076;
+
+/// Creates a scraper for the mock, which does not wait between requests or
+/// retry failed requests.
+fn scraper(mock: &MockCountdown) -> CountdownScraper {
+    retrying_scraper(
+        mock,
+        RetryConfig {
+            max_retries: 0,
+            ..RetryConfig::default()
+        },
+    )
+}
+
+/// Creates a scraper for the mock, which does not wait between requests, and
+/// retries failed requests as described by `retry`.
+fn retrying_scraper(mock: &MockCountdown, retry: RetryConfig) -> CountdownScraper {
+    let rate_limit = RateLimitConfig {
+        requests_per_second: 1000.0,
+        max_requests_per_second: 1000.0,
+        burst: 100,
+        ..RateLimitConfig::for_supermarket(&Supermarket::Countdown)
+    };
+
+    CountdownScraper::with_base_url(mock.uri(), retry, rate_limit).unwrap()
+}
+
+/// Caches products into a temporary directory, deleted once the test ends.
+fn cache() -> (TempDir, CacheConfig) {
+    let directory = TempDir::new().unwrap();
+    let config = CacheConfig {
+        directory: directory.path().to_path_buf(),
+        ..CacheConfig::default()
+    };
+
+    (directory, config)
+}
+
+/// Runs the scraper against the mock for the store.
+async fn run(mock: &MockCountdown, pool: &PgPool, cache: &CacheConfig, should_insert: bool) {
+    run_until(mock, pool, cache, should_insert, &Shutdown::never()).await;
+}
+
+/// Runs the scraper against the mock for the store, until `shutdown` is
+/// requested.
+async fn run_until(
+    mock: &MockCountdown,
+    pool: &PgPool,
+    cache: &CacheConfig,
+    should_insert: bool,
+    shutdown: &Shutdown,
+) {
+    scraper::run(
+        scraper(mock),
+        pool.clone(),
+        &StoreSelection::Only(vec![STORE_ID.to_string()]),
+        should_insert,
+        PriceStorage::Every,
+        None,
+        cache,
+        shutdown,
+    )
+    .await
+    .unwrap();
+}
+
+/// Runs the scraper against the mock for the store, inserting prices as
+/// described by `price_storage`.
+async fn run_storing(
+    mock: &MockCountdown,
+    pool: &PgPool,
+    cache: &CacheConfig,
+    price_storage: PriceStorage,
+) {
+    scraper::run(
+        scraper(mock),
+        pool.clone(),
+        &StoreSelection::Only(vec![STORE_ID.to_string()]),
+        true,
+        price_storage,
+        None,
+        cache,
+        &Shutdown::never(),
+    )
+    .await
+    .unwrap();
+}
+
+async fn count(pool: &PgPool, query: &str) -> i64 {
+    sqlx::query_scalar(query).fetch_one(pool).await.unwrap()
+}
+
+#[sqlx::test]
+async fn scrapes_every_page_of_every_category(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    mock.expect_pickup_location(STORE_ID).await;
+    let (cache_directory, cache) = cache();
+
+    run(&mock, &pool, &cache, true).await;
+
+    // bananas are in both categories, so are only saved once
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM countdown_products").await,
+        4
+    );
+    assert_eq!(
+        count(
+            &pool,
+            "SELECT COUNT(*) FROM prices WHERE run_id IS NOT NULL"
+        )
+        .await,
+        4
+    );
+
+    let bananas_categories: Vec<String> = sqlx::query_scalar(
+        r"SELECT categories.name FROM categories
+			INNER JOIN product_categories ON product_categories.category_id = categories.id
+			INNER JOIN products ON products.id = product_categories.product_id
+			INNER JOIN countdown_products ON countdown_products.id = products.countdown_id
+			WHERE countdown_products.sku = '133211'
+			ORDER BY categories.name",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(bananas_categories, ["Bakery", "Fruit & Veg"]);
+
+    let (completed, product_count, error_count): (bool, i32, i32) = sqlx::query_as(
+        "SELECT completed, product_count, error_count FROM scrape_runs WHERE store_key = $1",
+    )
+    .bind(STORE_ID.to_string())
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert!(completed);
+    assert_eq!(product_count, 4);
+    assert_eq!(error_count, 0);
+    assert_eq!(
+        count(
+            &pool,
+            "SELECT COUNT(*) FROM scrape_runs WHERE drift IS NULL"
+        )
+        .await,
+        1
+    );
+
+    let cached = std::fs::read_dir(
+        cache_directory
+            .path()
+            .join("countdown")
+            .join(STORE_ID.to_string()),
+    )
+    .unwrap()
+    .count();
+    assert_eq!(cached, 1);
+}
+
+#[sqlx::test]
+async fn records_pages_that_fail(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    mock.fail_page("fruit-veg", 2, 500).await;
+    let (_cache_directory, cache) = cache();
+
+    run(&mock, &pool, &cache, true).await;
+
+    // carrots are only on the failed page
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM countdown_products").await,
+        3
+    );
+
+    let (category, page): (String, i64) =
+        sqlx::query_as("SELECT category, page FROM scrape_run_failures")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(category, "Fruit & Veg");
+    assert_eq!(page, 2);
+
+    let (completed, product_count, error_count): (bool, i32, i32) =
+        sqlx::query_as("SELECT completed, product_count, error_count FROM scrape_runs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(completed);
+    assert_eq!(product_count, 3);
+    assert_eq!(error_count, 1);
+}
+
+#[sqlx::test]
+async fn retries_pages_that_fail_transiently(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    mock.fail_page_once("fruit-veg", 2, 503).await;
+    let (_cache_directory, cache) = cache();
+    let retry = RetryConfig {
+        max_retries: 1,
+        base_delay: Duration::from_millis(10),
+        ..RetryConfig::default()
+    };
+
+    scraper::run(
+        retrying_scraper(&mock, retry),
+        pool.clone(),
+        &StoreSelection::Only(vec![STORE_ID.to_string()]),
+        true,
+        PriceStorage::Every,
+        None,
+        &cache,
+        &Shutdown::never(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM countdown_products").await,
+        4
+    );
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM scrape_run_failures").await,
+        0
+    );
+}
+
+#[sqlx::test]
+async fn does_not_retry_pages_without_retries(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    mock.fail_page_once("fruit-veg", 2, 503).await;
+    let (_cache_directory, cache) = cache();
+
+    run(&mock, &pool, &cache, true).await;
+
+    // carrots are only on the failed page
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM countdown_products").await,
+        3
+    );
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM scrape_run_failures").await,
+        1
+    );
+}
+
+#[sqlx::test]
+async fn records_drift_in_the_responses(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    mock.serve_page("bakery", 1, "drift/products.json").await;
+    let (_cache_directory, cache) = cache();
+
+    run(&mock, &pool, &cache, true).await;
+
+    let drift: String = sqlx::query_scalar("SELECT drift::text FROM scrape_runs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let drift: serde_json::Value = serde_json::from_str(&drift).unwrap();
+    assert_eq!(drift["unknown_types"]["Recipe"], 2);
+    assert_eq!(
+        drift["unknown_fields"]["products.items[].price.memberPrice"],
+        1
+    );
+    // images are always sent, so are known to be ignored
+    assert!(drift["unknown_fields"]
+        .get("products.items[].images")
+        .is_none());
+}
+
+#[sqlx::test]
+async fn does_not_insert_when_told_not_to(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    let (_cache_directory, cache) = cache();
+
+    run(&mock, &pool, &cache, false).await;
+
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM countdown_products").await,
+        0
+    );
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM prices").await, 0);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM scrape_runs").await, 0);
+}
+
+#[sqlx::test]
+async fn leaves_the_run_resumable_when_shut_down(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    mock.delay_page("fruit-veg", 2, Duration::from_secs(30))
+        .await;
+    let (_cache_directory, cache) = cache();
+
+    let (trigger, shutdown) = Shutdown::new();
+    let shutdown_soon = async {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        trigger.request();
+    };
+    tokio::join!(
+        run_until(&mock, &pool, &cache, true, &shutdown),
+        shutdown_soon
+    );
+
+    let completed: bool = sqlx::query_scalar("SELECT completed FROM scrape_runs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(!completed);
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM prices").await, 0);
+
+    // the pages that were retrieved are kept, so the run can be resumed
+    assert!(count(&pool, "SELECT COUNT(*) FROM scrape_run_pages").await > 0);
+}
+
+#[sqlx::test]
+async fn resumes_only_the_pending_pages(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    mock.delay_page("fruit-veg", 2, Duration::from_secs(30))
+        .await;
+    let (_cache_directory, cache) = cache();
+
+    let (trigger, shutdown) = Shutdown::new();
+    let shutdown_soon = async {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        trigger.request();
+    };
+    tokio::join!(
+        run_until(&mock, &pool, &cache, true, &shutdown),
+        shutdown_soon
+    );
+    let run_id: i32 = sqlx::query_scalar("SELECT id FROM scrape_runs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    // a failure of the previous attempt, whose page is still pending
+    sqlx::query(
+        "INSERT INTO scrape_run_failures (run_id, category, page, error) VALUES ($1, 'Fruit & Veg', 2, 'timed out')",
+    )
+    .bind(run_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let resumed = MockCountdown::start().await;
+    resumed.expect_page("bakery", 1, 0).await;
+    resumed.expect_page("fruit-veg", 1, 0).await;
+    resumed.expect_page("fruit-veg", 2, 1).await;
+    scraper::run(
+        scraper(&resumed),
+        pool.clone(),
+        &StoreSelection::Default,
+        true,
+        PriceStorage::Every,
+        Some(run_id),
+        &cache,
+        &Shutdown::never(),
+    )
+    .await
+    .unwrap();
+    resumed.server.verify().await;
+
+    let (completed, product_count, error_count): (bool, i32, i32) =
+        sqlx::query_as("SELECT completed, product_count, error_count FROM scrape_runs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(completed);
+    assert_eq!(product_count, 4);
+    assert_eq!(error_count, 0);
+    assert_eq!(
+        count(&pool, "SELECT COUNT(*) FROM scrape_run_failures").await,
+        0
+    );
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM prices").await, 4);
+}
+
+#[sqlx::test]
+async fn fails_while_another_run_scrapes_the_store(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    let (_cache_directory, cache) = cache();
+
+    // the store is saved by the first run
+    run(&mock, &pool, &cache, true).await;
+    let store_id: i32 = sqlx::query_scalar("SELECT id FROM stores WHERE countdown_store_id = $1")
+        .bind(STORE_ID)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    let lock = scraper::lock_store(&pool, store_id).await.unwrap().unwrap();
+    let result = scraper::run(
+        scraper(&mock),
+        pool.clone(),
+        &StoreSelection::Only(vec![STORE_ID.to_string()]),
+        true,
+        PriceStorage::Every,
+        None,
+        &cache,
+        &Shutdown::never(),
+    )
+    .await;
+    assert!(result.is_err());
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM scrape_runs").await, 1);
+
+    lock.release().await.unwrap();
+    run(&mock, &pool, &cache, true).await;
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM scrape_runs").await, 2);
+}
+
+#[sqlx::test]
+async fn archives_pages_under_the_slug_of_their_category(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    let (_cache_directory, cache) = cache();
+    let archive_directory = TempDir::new().unwrap();
+    let cache = CacheConfig {
+        archive_directory: Some(archive_directory.path().to_path_buf()),
+        ..cache
+    };
+
+    run(&mock, &pool, &cache, false).await;
+
+    // archived as `<root>/countdown/<store>/<timestamp>/`
+    let store_directory = archive_directory
+        .path()
+        .join("countdown")
+        .join(STORE_ID.to_string());
+    let run_directory = fs::read_dir(store_directory)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let mut names: Vec<_> = fs::read_dir(run_directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "products-bakery-0001.json.gz",
+            "products-fruit-veg-0001.json.gz",
+            "products-fruit-veg-0002.json.gz",
+            "shell.json.gz",
+        ]
+    );
+}
+
+#[sqlx::test]
+async fn only_inserts_changed_prices_when_told_to(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    let (_cache_directory, cache) = cache();
+
+    run_storing(&mock, &pool, &cache, PriceStorage::Changes).await;
+    run_storing(&mock, &pool, &cache, PriceStorage::Changes).await;
+
+    // nothing changed, so only the first run inserted prices
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM prices").await, 4);
+    assert_eq!(
+        count(
+            &pool,
+            "SELECT COUNT(*) FROM latest_prices WHERE last_run_id = (SELECT MAX(id) FROM scrape_runs)"
+        )
+        .await,
+        4
+    );
+
+    mock.serve_page("bakery", 1, "changed/products.json").await;
+    run_storing(&mock, &pool, &cache, PriceStorage::Changes).await;
+
+    // only the bread changed price
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM prices").await, 5);
+    let cost_in_cents: i32 = sqlx::query_scalar(
+        r"SELECT prices.cost_in_cents FROM latest_prices
+			INNER JOIN prices ON prices.id = latest_prices.price_id
+			INNER JOIN products ON products.id = latest_prices.product_id
+			INNER JOIN countdown_products ON countdown_products.id = products.countdown_id
+			WHERE countdown_products.sku = '281569'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(cost_in_cents, 490);
+
+    // every price is inserted otherwise
+    run_storing(&mock, &pool, &cache, PriceStorage::Every).await;
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM prices").await, 9);
+}
+
+#[sqlx::test]
+async fn inserts_prices_whose_promotions_changed(pool: PgPool) {
+    let mock = MockCountdown::start().await;
+    let (_cache_directory, cache) = cache();
+
+    run_storing(&mock, &pool, &cache, PriceStorage::Changes).await;
+    mock.serve_page("bakery", 1, "promoted/products.json").await;
+    run_storing(&mock, &pool, &cache, PriceStorage::Changes).await;
+
+    // only the bread gained a multi-buy, its price is unchanged
+    assert_eq!(count(&pool, "SELECT COUNT(*) FROM prices").await, 5);
+    let promotion_prices: Vec<i32> = sqlx::query_scalar(
+        r"SELECT promotions.price_in_cents FROM latest_prices
+			INNER JOIN promotions ON promotions.price_id = latest_prices.price_id
+			INNER JOIN products ON products.id = latest_prices.product_id
+			INNER JOIN countdown_products ON countdown_products.id = products.countdown_id
+			WHERE countdown_products.sku = '281569'",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(promotion_prices, [1000]);
+}